@@ -0,0 +1,160 @@
+//! Batch inputs: output files, JSONL files holding one output per line, and
+//! directories of either. Outputs are read and parsed in parallel.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use serde_json::Value;
+
+use llmc::contract::Contract;
+use llmc::extract::Extraction;
+use llmc::verifier::{parse_output, read_output, RunError, Verdict};
+
+/// An output's text, its parsed value and, for text outputs, where the JSON
+/// was extracted from.
+pub type LoadedOutput = Result<(String, Value, Option<Extraction>), RunError>;
+
+/// Where an output came from: a file, or one line of a JSONL file.
+#[derive(Debug, Clone)]
+pub struct OutputSource {
+    pub path: PathBuf,
+    /// 1-based line number and byte offset of a JSONL record.
+    pub record: Option<(usize, usize)>,
+}
+
+impl OutputSource {
+    fn file(path: &Path) -> Self {
+        OutputSource {
+            path: path.to_path_buf(),
+            record: None,
+        }
+    }
+
+    /// How the output is named in batch verdicts, e.g. `results.jsonl:3`.
+    pub fn label(&self) -> String {
+        match self.record {
+            Some((line, _)) => format!("{}:{line}", self.path.display()),
+            None => self.path.display().to_string(),
+        }
+    }
+
+    /// Moves positions computed within a JSONL record to where the record
+    /// sits in its file.
+    pub fn shift_positions(&self, verdict: &mut Verdict) {
+        let Some((line, offset)) = self.record else {
+            return;
+        };
+        let violation_positions = verdict
+            .violations
+            .iter_mut()
+            .flat_map(|violation| [&mut violation.position, &mut violation.end_position])
+            .flatten();
+        let extraction_positions = verdict
+            .extraction
+            .iter_mut()
+            .flat_map(|extraction| [&mut extraction.position, &mut extraction.end_position]);
+        for position in violation_positions.chain(extraction_positions) {
+            position.line += line - 1;
+            position.offset += offset;
+        }
+    }
+}
+
+/// Every output to verify, in order: the `files`, each line of the `jsonl`
+/// files, then the `.json` and `.jsonl` files of each of `dirs` sorted by
+/// name. Outputs are parsed as the contract's `output_format` says. Anything
+/// that cannot be read is kept as an error, so it gets a verdict of its own.
+pub fn load_outputs(
+    contract: &Contract,
+    files: &[PathBuf],
+    jsonl: &[PathBuf],
+    dirs: &[PathBuf],
+) -> Vec<(OutputSource, LoadedOutput)> {
+    let mut loaded: Vec<_> = files
+        .par_iter()
+        .map(|path| (OutputSource::file(path), load_file(contract, path)))
+        .collect();
+    loaded.par_extend(
+        jsonl
+            .par_iter()
+            .flat_map_iter(|path| load_jsonl(contract, path)),
+    );
+    for dir in dirs {
+        match dir_outputs(dir) {
+            Ok(paths) if paths.is_empty() => {
+                loaded.push((OutputSource::file(dir), Err(no_outputs(dir))))
+            }
+            Ok(paths) => loaded.par_extend(paths.par_iter().flat_map_iter(|path| {
+                if is_jsonl(path) {
+                    load_jsonl(contract, path)
+                } else {
+                    vec![(OutputSource::file(path), load_file(contract, path))]
+                }
+            })),
+            Err(err) => loaded.push((OutputSource::file(dir), Err(RunError::Io(err)))),
+        }
+    }
+    loaded
+}
+
+fn load_file(contract: &Contract, path: &Path) -> LoadedOutput {
+    let text = read_output(path)?;
+    let (output, extraction) = parse_output(contract, &text)?;
+    Ok((text, output, extraction))
+}
+
+// Blank lines are skipped; a file without any record is an error.
+fn load_jsonl(contract: &Contract, path: &Path) -> Vec<(OutputSource, LoadedOutput)> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => return vec![(OutputSource::file(path), Err(RunError::Io(err)))],
+    };
+    let mut offset = 0;
+    let mut records = Vec::new();
+    for (idx, line) in contents.split_inclusive('\n').enumerate() {
+        if !line.trim().is_empty() {
+            records.push((idx + 1, offset, line.trim_end()));
+        }
+        offset += line.len();
+    }
+    if records.is_empty() {
+        return vec![(OutputSource::file(path), Err(no_outputs(path)))];
+    }
+    records
+        .into_par_iter()
+        .map(|(line, offset, text)| {
+            let source = OutputSource {
+                path: path.to_path_buf(),
+                record: Some((line, offset)),
+            };
+            let output = parse_output(contract, text)
+                .map(|(output, extraction)| (text.to_string(), output, extraction));
+            (source, output)
+        })
+        .collect()
+}
+
+// The `.json` and `.jsonl` files directly inside `dir`, sorted by name.
+fn dir_outputs(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+    paths.retain(|path| {
+        path.is_file() && (is_jsonl(path) || path.extension().is_some_and(|ext| ext == "json"))
+    });
+    paths.sort();
+    Ok(paths)
+}
+
+fn is_jsonl(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "jsonl")
+}
+
+fn no_outputs(path: &Path) -> RunError {
+    RunError::Io(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("no outputs in {}", path.display()),
+    ))
+}
@@ -0,0 +1,105 @@
+use crate::contract::CheckDigitScheme;
+
+impl CheckDigitScheme {
+    pub fn name(self) -> &'static str {
+        match self {
+            CheckDigitScheme::Luhn => "luhn",
+            CheckDigitScheme::Isbn10 => "isbn10",
+            CheckDigitScheme::Isbn13 => "isbn13",
+            CheckDigitScheme::Ean => "ean",
+        }
+    }
+}
+
+/// Validates `code` under `scheme`. Spaces and hyphens are ignored, so
+/// `978-0-306-40615-7` is accepted as an ISBN-13.
+pub fn check_digit(code: &str, scheme: CheckDigitScheme) -> Result<(), String> {
+    let compact: Vec<char> = code.chars().filter(|c| !matches!(c, ' ' | '-')).collect();
+    match scheme {
+        CheckDigitScheme::Luhn => {
+            let digits = digits(&compact)?;
+            if digits.len() < 2 {
+                return Err("expected at least 2 digits".to_string());
+            }
+            verify(luhn_sum(&digits).is_multiple_of(10))
+        }
+        CheckDigitScheme::Isbn10 => {
+            if compact.len() != 10 {
+                return Err(format!("expected 10 characters, found {}", compact.len()));
+            }
+            let mut values = digits(&compact[..9])?;
+            values.push(match compact[9] {
+                'X' | 'x' => 10,
+                c => c.to_digit(10).ok_or("invalid check character")?,
+            });
+            let sum: u32 = values
+                .iter()
+                .enumerate()
+                .map(|(idx, value)| (10 - idx as u32) * value)
+                .sum();
+            verify(sum.is_multiple_of(11))
+        }
+        CheckDigitScheme::Isbn13 => {
+            let digits = digits(&compact)?;
+            if digits.len() != 13 {
+                return Err(format!("expected 13 digits, found {}", digits.len()));
+            }
+            if !matches!(digits[..3], [9, 7, 8] | [9, 7, 9]) {
+                return Err("expected a 978 or 979 prefix".to_string());
+            }
+            verify(gtin_sum(&digits).is_multiple_of(10))
+        }
+        CheckDigitScheme::Ean => {
+            let digits = digits(&compact)?;
+            if !matches!(digits.len(), 8 | 12 | 13 | 14) {
+                return Err(format!(
+                    "expected 8, 12, 13 or 14 digits, found {}",
+                    digits.len()
+                ));
+            }
+            verify(gtin_sum(&digits).is_multiple_of(10))
+        }
+    }
+}
+
+fn digits(chars: &[char]) -> Result<Vec<u32>, String> {
+    chars
+        .iter()
+        .map(|c| {
+            c.to_digit(10)
+                .ok_or_else(|| format!("unexpected character '{c}'"))
+        })
+        .collect()
+}
+
+fn verify(valid: bool) -> Result<(), String> {
+    if valid {
+        Ok(())
+    } else {
+        Err("check digit mismatch".to_string())
+    }
+}
+
+// Doubles every second digit from the right, including the check digit's sum.
+fn luhn_sum(digits: &[u32]) -> u32 {
+    digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(idx, &digit)| match (idx % 2, digit * 2) {
+            (0, _) => digit,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum()
+}
+
+// GTIN weighting: 1 for the check digit, then alternating 3 and 1 to the left.
+fn gtin_sum(digits: &[u32]) -> u32 {
+    digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(idx, &digit)| if idx % 2 == 1 { digit * 3 } else { digit })
+        .sum()
+}
@@ -0,0 +1,92 @@
+//! Stable violation codes. Each rule kind, and failure modes shared across
+//! rules, has a code that never changes meaning, so automation and baselines
+//! can key on it instead of the English message.
+
+/// The output does not have the contract's `output_type`.
+pub const OUTPUT_TYPE: &str = "LLMC001";
+/// The output, or a row of it, does not have the shape a rule needs
+/// (e.g. a row that is not an object, or `min_items` on an object).
+pub const UNSUITABLE_SHAPE: &str = "LLMC002";
+
+pub const MISSING_FIELD: &str = "LLMC101";
+/// `field_type` found no field to check.
+pub const MISSING_TYPED_FIELD: &str = "LLMC102";
+/// Strict mode: a row has a field no rule of the contract mentions.
+pub const UNKNOWN_FIELD: &str = "LLMC103";
+
+pub const TYPE_MISMATCH: &str = "LLMC201";
+/// A field holds a value of a type the rule cannot evaluate, e.g. a number
+/// checked by `regex`.
+pub const UNSUITABLE_VALUE_TYPE: &str = "LLMC202";
+
+pub const DISALLOWED_VALUE: &str = "LLMC301";
+pub const CONST_MISMATCH: &str = "LLMC302";
+pub const PATTERN_MISMATCH: &str = "LLMC303";
+pub const VALUES_NOT_COVERED: &str = "LLMC304";
+pub const INVALID_EMBEDDED_JSON: &str = "LLMC305";
+pub const INVALID_CHECK_DIGIT: &str = "LLMC306";
+pub const INVALID_COORDINATE: &str = "LLMC307";
+pub const INVALID_DATETIME: &str = "LLMC308";
+pub const INVALID_LOCALE_NUMBER: &str = "LLMC309";
+pub const NUMBER_OUT_OF_RANGE: &str = "LLMC310";
+pub const STRING_LENGTH: &str = "LLMC311";
+
+pub const TOO_FEW_ITEMS: &str = "LLMC401";
+pub const EMPTY_ROW: &str = "LLMC402";
+pub const ARRAY_LENGTH: &str = "LLMC403";
+pub const KEY_ORDER: &str = "LLMC404";
+pub const TOO_MANY_ITEMS: &str = "LLMC405";
+/// `unique_values`: a value repeats within one output.
+pub const DUPLICATE_VALUE: &str = "LLMC406";
+
+pub const NOT_APPROX_EQUAL: &str = "LLMC501";
+pub const DATETIME_ORDER: &str = "LLMC502";
+pub const COMPARISON_FAILED: &str = "LLMC503";
+
+pub const DUPLICATE_ACROSS_OUTPUTS: &str = "LLMC601";
+pub const INCONSISTENT_ACROSS_OUTPUTS: &str = "LLMC602";
+
+/// `any_of` or `one_of` matched none of its groups.
+pub const NO_MATCHING_GROUP: &str = "LLMC701";
+/// `one_of` matched more than one group.
+pub const MULTIPLE_MATCHING_GROUPS: &str = "LLMC702";
+pub const NEGATED_RULES_MATCH: &str = "LLMC703";
+
+pub const INVALID_CONTRACT: &str = "LLMC901";
+pub const RUNTIME_ERROR: &str = "LLMC902";
+
+/// The code for the main failure mode of the rule reported as `rule_name`.
+pub fn default_code(rule_name: &str) -> &'static str {
+    match rule_name {
+        "OutputType" => OUTPUT_TYPE,
+        "RequiredField" => MISSING_FIELD,
+        "UnknownField" => UNKNOWN_FIELD,
+        "FieldType" => TYPE_MISMATCH,
+        "AllowedValues" => DISALLOWED_VALUE,
+        "Const" => CONST_MISMATCH,
+        "Regex" => PATTERN_MISMATCH,
+        "CoversValues" => VALUES_NOT_COVERED,
+        "EmbeddedJson" => INVALID_EMBEDDED_JSON,
+        "CheckDigit" => INVALID_CHECK_DIGIT,
+        "GeoCoordinate" => INVALID_COORDINATE,
+        "DatetimeFormat" => INVALID_DATETIME,
+        "LocaleNumber" => INVALID_LOCALE_NUMBER,
+        "NumberRange" => NUMBER_OUT_OF_RANGE,
+        "StringLength" => STRING_LENGTH,
+        "MinItems" => TOO_FEW_ITEMS,
+        "MaxItems" => TOO_MANY_ITEMS,
+        "NoEmptyRows" => EMPTY_ROW,
+        "FieldArrayLength" => ARRAY_LENGTH,
+        "KeyOrder" => KEY_ORDER,
+        "UniqueValues" => DUPLICATE_VALUE,
+        "ApproxEqual" => NOT_APPROX_EQUAL,
+        "DatetimeOrder" => DATETIME_ORDER,
+        "FieldComparison" => COMPARISON_FAILED,
+        "UniqueAcrossOutputs" => DUPLICATE_ACROSS_OUTPUTS,
+        "ConsistentAcrossOutputs" => INCONSISTENT_ACROSS_OUTPUTS,
+        "AnyOf" | "OneOf" => NO_MATCHING_GROUP,
+        "Not" => NEGATED_RULES_MATCH,
+        "InvalidContract" => INVALID_CONTRACT,
+        _ => RUNTIME_ERROR,
+    }
+}
@@ -1,6 +1,13 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::path::Path;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::patterns::builtin_pattern;
+use crate::verifier::{self, RunError};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Contract {
@@ -8,7 +15,172 @@ pub struct Contract {
     pub version: Option<u32>,
     pub inputs: Vec<String>,
     pub output_type: OutputType,
-    pub rules: Vec<Rule>,
+    /// How output text is parsed; `text_with_json` extracts the JSON from
+    /// surrounding text such as code fences and prose.
+    #[serde(default, skip_serializing_if = "OutputFormat::is_json")]
+    pub output_format: OutputFormat,
+    pub rules: Vec<RuleSpec>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<RuleGroup>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scoring: Option<Scoring>,
+    /// Row mode: the output passes when at least this fraction of rows pass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub row_pass_threshold: Option<f64>,
+    /// Row mode: the output fails below this fraction of passing rows and is
+    /// `partial` between it and `row_pass_threshold`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub row_fail_threshold: Option<f64>,
+    /// Named regex patterns referenced by `regex` rules through `pattern_ref`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub patterns: BTreeMap<String, String>,
+    /// Language of violation messages, e.g. `de`. Defaults to English.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Message templates per locale, keyed by violation code, overriding the
+    /// built-in catalogs.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub messages: BTreeMap<String, BTreeMap<String, String>>,
+    /// Strict mode: absent fields fail `allowed_values` and `regex`, row keys
+    /// no rule mentions are violations, and duplicate rules are rejected.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict: bool,
+}
+
+impl Contract {
+    /// Parses and validates a contract from JSON, e.g. one embedded with
+    /// `include_str!`. `values_file` paths are resolved against the current
+    /// directory.
+    pub fn from_json_str(json: &str) -> Result<Self, RunError> {
+        let mut contract = verifier::parse_contract(json, None)?;
+        verifier::load_values_files(&mut contract, Path::new(""))?;
+        verifier::validate_contract(&contract)?;
+        Ok(contract)
+    }
+
+    /// Every rule of the contract in evaluation order: ungrouped rules first,
+    /// then the rules of each group, paired with the group they belong to.
+    pub fn rule_specs(&self) -> impl Iterator<Item = (Option<&RuleGroup>, &RuleSpec)> {
+        self.rules.iter().map(|spec| (None, spec)).chain(
+            self.groups
+                .iter()
+                .flat_map(|group| group.rules.iter().map(move |spec| (Some(group), spec))),
+        )
+    }
+
+    /// Resolves a `regex` rule's pattern: an inline `pattern` wins, otherwise
+    /// `pattern_ref` is looked up in the contract's `patterns` and then in the
+    /// built-in library.
+    pub fn resolve_pattern<'a>(
+        &'a self,
+        pattern: Option<&'a str>,
+        pattern_ref: Option<&str>,
+    ) -> Option<&'a str> {
+        pattern.or_else(|| {
+            let name = pattern_ref?;
+            self.patterns
+                .get(name)
+                .map(String::as_str)
+                .or_else(|| builtin_pattern(name))
+        })
+    }
+}
+
+/// Several named contracts shipped as one file, selected at run time by
+/// name, with definitions they share.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContractPack {
+    pub pack: Option<String>,
+    /// Version of the pack as a whole; contracts without their own inherit it.
+    pub version: Option<u32>,
+    /// Named regex patterns available to every contract of the pack.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub patterns: BTreeMap<String, String>,
+    /// Default message locale of the pack's contracts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Message templates per locale shared by the pack's contracts.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub messages: BTreeMap<String, BTreeMap<String, String>>,
+    pub contracts: BTreeMap<String, Contract>,
+}
+
+impl ContractPack {
+    /// The contract named `name`, with the pack's shared definitions merged
+    /// in. Definitions of the contract itself win over shared ones.
+    pub fn select(mut self, name: &str) -> Option<Contract> {
+        let mut contract = self.contracts.remove(name)?;
+        for (pattern_name, pattern) in self.patterns {
+            contract.patterns.entry(pattern_name).or_insert(pattern);
+        }
+        for (locale, messages) in self.messages {
+            let overrides = contract.messages.entry(locale).or_default();
+            for (code, message) in messages {
+                overrides.entry(code).or_insert(message);
+            }
+        }
+        contract.locale = contract.locale.or(self.locale);
+        contract.version = contract.version.or(self.version);
+        contract.contract.get_or_insert_with(|| name.to_string());
+        Some(contract)
+    }
+}
+
+/// A named set of rules reported together in the verdict.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleGroup {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(default)]
+    pub mode: GroupMode,
+    pub rules: Vec<RuleSpec>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupMode {
+    /// The group passes when all of its rules pass.
+    #[default]
+    All,
+    /// The group passes when at least one of its rules passes.
+    Any,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    /// Whether this severity is `threshold` or more severe.
+    pub fn at_least(self, threshold: Severity) -> bool {
+        self.rank() >= threshold.rank()
+    }
+
+    fn rank(self) -> u8 {
+        match self {
+            Severity::Info => 0,
+            Severity::Warning => 1,
+            Severity::Error => 2,
+        }
+    }
+}
+
+/// Scoring mode: the verdict carries a weighted score in `[0, 1]` and passes
+/// when the score reaches `pass_threshold`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scoring {
+    pub pass_threshold: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -18,15 +190,445 @@ pub enum OutputType {
     Array,
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// The output is a JSON document.
+    #[default]
+    Json,
+    /// The output is text holding a JSON object or array, e.g. a model
+    /// transcript with a fenced code block.
+    TextWithJson,
+}
+
+impl OutputFormat {
+    fn is_json(&self) -> bool {
+        *self == OutputFormat::Json
+    }
+}
+
+/// A top-level contract rule together with its evaluation metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleSpec {
+    #[serde(flatten)]
+    pub rule: Rule,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+    /// Warn-only rules (`enforce: false`) report violations without failing the verdict.
+    #[serde(default = "enforced_by_default", skip_serializing_if = "is_enforced")]
+    pub enforce: bool,
+    /// Overrides the severity inherited from the rule's group (default `error`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    /// Replaces the generated fix hint reported with the rule's violations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+fn enforced_by_default() -> bool {
+    true
+}
+
+fn is_enforced(enforce: &bool) -> bool {
+    *enforce
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "rule", rename_all = "snake_case", deny_unknown_fields)]
 pub enum Rule {
-    RequiredField { field: String },
-    FieldType { field: String, expected: ValueType },
-    AllowedValues { field: String, values: Vec<Value> },
-    Regex { field: String, pattern: String },
-    MinItems { value: u64 },
+    RequiredField {
+        field: String,
+        /// Value suggested to fill in when the field is missing.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<Value>,
+    },
+    FieldType {
+        field: String,
+        expected: ValueType,
+    },
+    AllowedValues {
+        field: String,
+        #[serde(default)]
+        values: Vec<Value>,
+        /// CSV, JSON or newline-separated list resolved relative to the contract file.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        values_file: Option<String>,
+        /// Skips the first row of a CSV `values_file`, which names its columns.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        values_file_header: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        normalize: Option<Normalization>,
+    },
+    /// Every one of `values` appears in `field` of at least one row.
+    CoversValues {
+        field: String,
+        values: Vec<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        normalize: Option<Normalization>,
+    },
+    /// Field equal to a fixed value.
+    Const {
+        field: String,
+        value: Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        normalize: Option<Normalization>,
+    },
+    Regex {
+        field: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pattern_ref: Option<String>,
+    },
+    MinItems {
+        value: u64,
+    },
+    /// Top-level array of at most `value` items.
+    MaxItems {
+        value: u64,
+    },
     NoEmptyRows,
+    /// Object keys appearing in the order of `keys`, checked per object or
+    /// row. Unlisted keys may sit anywhere unless `exact` is set, which also
+    /// requires every listed key and nothing else.
+    KeyOrder {
+        keys: Vec<String>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        exact: bool,
+    },
+    /// String field holding serialized JSON whose top-level value is of type `expected`.
+    EmbeddedJson {
+        field: String,
+        expected: ValueType,
+    },
+    /// String field carrying a valid check digit under `scheme`.
+    CheckDigit {
+        field: String,
+        scheme: CheckDigitScheme,
+    },
+    /// Latitude/longitude given as a `[latitude, longitude]` pair in `field`
+    /// or as separate `latitude` and `longitude` fields, optionally confined
+    /// to a bounding box.
+    GeoCoordinate {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        field: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        latitude: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        longitude: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bbox: Option<BoundingBox>,
+    },
+    /// Array field whose length lies within `min..=max`, checked per object or row.
+    FieldArrayLength {
+        field: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<u64>,
+    },
+    /// Numeric field within `min..=max`.
+    NumberRange {
+        field: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<f64>,
+    },
+    /// String field whose length in characters lies within `min..=max`.
+    StringLength {
+        field: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<u64>,
+    },
+    /// Numeric field equal to `target` (or to `other_field`) within a tolerance.
+    ApproxEqual {
+        field: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        target: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        other_field: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        abs_tolerance: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rel_tolerance: Option<f64>,
+    },
+    /// String field parsing under a chrono `format` string or a named `preset`.
+    DatetimeFormat {
+        field: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        format: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        preset: Option<DatetimePreset>,
+    },
+    /// Two datetime fields ordered by `operator` (default: strictly before),
+    /// compared as instants after applying their UTC offsets.
+    DatetimeOrder {
+        field: String,
+        other_field: String,
+        #[serde(default = "strictly_before")]
+        operator: ComparisonOperator,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        format: Option<String>,
+        /// Offset such as `+02:00` assumed for values without one; defaults to UTC.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timezone: Option<String>,
+    },
+    /// Field related to `other_field` by `operator`: numbers compare
+    /// numerically, strings lexicographically (so ISO dates compare by date).
+    FieldComparison {
+        field: String,
+        operator: ComparisonOperator,
+        other_field: String,
+    },
+    /// Numeric string written in a locale's convention (`"1.234,56"` for `de`),
+    /// optionally bounded after parsing.
+    LocaleNumber {
+        field: String,
+        locale: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<f64>,
+    },
+    /// Field values unique across the rows of one output.
+    UniqueValues {
+        field: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        normalize: Option<Normalization>,
+    },
+    /// Field values unique across every row of every output verified in one run.
+    UniqueAcrossOutputs {
+        field: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        normalize: Option<Normalization>,
+    },
+    /// Rows sharing a `key` value carry identical values for each of `fields`
+    /// across every output verified in one run.
+    ConsistentAcrossOutputs {
+        key: String,
+        fields: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        normalize: Option<Normalization>,
+    },
+    AnyOf {
+        groups: Vec<Vec<Rule>>,
+    },
+    OneOf {
+        groups: Vec<Vec<Rule>>,
+    },
+    Not {
+        rules: Vec<Rule>,
+    },
+}
+
+impl Rule {
+    /// The rule's name as written in contracts, e.g. `required_field`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::RequiredField { .. } => "required_field",
+            Rule::FieldType { .. } => "field_type",
+            Rule::AllowedValues { .. } => "allowed_values",
+            Rule::CoversValues { .. } => "covers_values",
+            Rule::Const { .. } => "const",
+            Rule::Regex { .. } => "regex",
+            Rule::MinItems { .. } => "min_items",
+            Rule::MaxItems { .. } => "max_items",
+            Rule::NoEmptyRows => "no_empty_rows",
+            Rule::KeyOrder { .. } => "key_order",
+            Rule::EmbeddedJson { .. } => "embedded_json",
+            Rule::CheckDigit { .. } => "check_digit",
+            Rule::GeoCoordinate { .. } => "geo_coordinate",
+            Rule::FieldArrayLength { .. } => "field_array_length",
+            Rule::NumberRange { .. } => "number_range",
+            Rule::StringLength { .. } => "string_length",
+            Rule::ApproxEqual { .. } => "approx_equal",
+            Rule::DatetimeFormat { .. } => "datetime_format",
+            Rule::DatetimeOrder { .. } => "datetime_order",
+            Rule::FieldComparison { .. } => "field_comparison",
+            Rule::LocaleNumber { .. } => "locale_number",
+            Rule::UniqueValues { .. } => "unique_values",
+            Rule::UniqueAcrossOutputs { .. } => "unique_across_outputs",
+            Rule::ConsistentAcrossOutputs { .. } => "consistent_across_outputs",
+            Rule::AnyOf { .. } => "any_of",
+            Rule::OneOf { .. } => "one_of",
+            Rule::Not { .. } => "not",
+        }
+    }
+
+    /// The field the rule checks, if it targets one.
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Rule::RequiredField { field, .. }
+            | Rule::FieldType { field, .. }
+            | Rule::AllowedValues { field, .. }
+            | Rule::CoversValues { field, .. }
+            | Rule::Const { field, .. }
+            | Rule::Regex { field, .. }
+            | Rule::EmbeddedJson { field, .. }
+            | Rule::CheckDigit { field, .. }
+            | Rule::FieldArrayLength { field, .. }
+            | Rule::NumberRange { field, .. }
+            | Rule::StringLength { field, .. }
+            | Rule::ApproxEqual { field, .. }
+            | Rule::DatetimeFormat { field, .. }
+            | Rule::DatetimeOrder { field, .. }
+            | Rule::FieldComparison { field, .. }
+            | Rule::LocaleNumber { field, .. }
+            | Rule::UniqueValues { field, .. }
+            | Rule::UniqueAcrossOutputs { field, .. } => Some(field),
+            Rule::GeoCoordinate {
+                field, latitude, ..
+            } => field.as_deref().or(latitude.as_deref()),
+            Rule::ConsistentAcrossOutputs { key, .. } => Some(key),
+            Rule::MinItems { .. }
+            | Rule::MaxItems { .. }
+            | Rule::NoEmptyRows
+            | Rule::KeyOrder { .. }
+            | Rule::AnyOf { .. }
+            | Rule::OneOf { .. }
+            | Rule::Not { .. } => None,
+        }
+    }
+
+    /// Every row field the rule reads, including those of nested rules.
+    pub fn referenced_fields(&self) -> Vec<&str> {
+        match self {
+            Rule::GeoCoordinate {
+                field,
+                latitude,
+                longitude,
+                ..
+            } => [field, latitude, longitude]
+                .into_iter()
+                .filter_map(|field| field.as_deref())
+                .collect(),
+            Rule::ApproxEqual {
+                field, other_field, ..
+            } => std::iter::once(field.as_str())
+                .chain(other_field.as_deref())
+                .collect(),
+            Rule::DatetimeOrder {
+                field, other_field, ..
+            }
+            | Rule::FieldComparison {
+                field, other_field, ..
+            } => vec![field, other_field],
+            Rule::KeyOrder { keys, .. } => keys.iter().map(String::as_str).collect(),
+            Rule::ConsistentAcrossOutputs { key, fields, .. } => std::iter::once(key)
+                .chain(fields)
+                .map(String::as_str)
+                .collect(),
+            Rule::AnyOf { groups } | Rule::OneOf { groups } => groups
+                .iter()
+                .flatten()
+                .flat_map(Rule::referenced_fields)
+                .collect(),
+            Rule::Not { rules } => rules.iter().flat_map(Rule::referenced_fields).collect(),
+            _ => self.field().into_iter().collect(),
+        }
+    }
+}
+
+/// String comparison options for rules that compare values for equality.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Normalization {
+    #[serde(default)]
+    pub case_fold: bool,
+    #[serde(default)]
+    pub strip_diacritics: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unicode: Option<UnicodeForm>,
+    /// Trims and collapses runs of whitespace into a single space.
+    #[serde(default)]
+    pub collapse_whitespace: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UnicodeForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+fn strictly_before() -> ComparisonOperator {
+    ComparisonOperator::Lt
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonOperator {
+    #[serde(alias = "<")]
+    Lt,
+    #[serde(alias = "<=")]
+    Lte,
+    #[serde(alias = ">")]
+    Gt,
+    #[serde(alias = ">=")]
+    Gte,
+    #[serde(alias = "==")]
+    Eq,
+    #[serde(alias = "!=")]
+    Ne,
+}
+
+impl ComparisonOperator {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            ComparisonOperator::Lt => "<",
+            ComparisonOperator::Lte => "<=",
+            ComparisonOperator::Gt => ">",
+            ComparisonOperator::Gte => ">=",
+            ComparisonOperator::Eq => "==",
+            ComparisonOperator::Ne => "!=",
+        }
+    }
+
+    /// Whether `left <operator> right` holds given `left.cmp(right)`.
+    pub fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            ComparisonOperator::Lt => ordering == Ordering::Less,
+            ComparisonOperator::Lte => ordering != Ordering::Greater,
+            ComparisonOperator::Gt => ordering == Ordering::Greater,
+            ComparisonOperator::Gte => ordering != Ordering::Less,
+            ComparisonOperator::Eq => ordering == Ordering::Equal,
+            ComparisonOperator::Ne => ordering != Ordering::Equal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DatetimePreset {
+    Rfc3339,
+    Rfc2822,
+    IsoDate,
+}
+
+/// Inclusive latitude/longitude bounds in decimal degrees.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BoundingBox {
+    pub min_lat: f64,
+    pub max_lat: f64,
+    pub min_lon: f64,
+    pub max_lon: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckDigitScheme {
+    Luhn,
+    Isbn10,
+    Isbn13,
+    /// EAN-8, UPC-A (GTIN-12), EAN-13 and GTIN-14.
+    #[serde(alias = "gtin")]
+    Ean,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -0,0 +1,58 @@
+//! Contract coverage over a corpus of outputs: rules that never fired and
+//! fields no rule guards.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use llmc::contract::Contract;
+use llmc::verifier::Verdict;
+
+use crate::metrics::{metrics, RuleMetrics};
+use crate::profile::profile;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Coverage {
+    pub outputs: usize,
+    /// Every contract rule with its violation counts over the corpus.
+    pub rules: Vec<RuleMetrics>,
+    /// Rules that no output violated: possibly dead, or too lenient.
+    pub never_violated: Vec<RuleMetrics>,
+    /// Top-level row fields seen in the outputs that no rule reads.
+    pub unguarded_fields: Vec<UnguardedField>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnguardedField {
+    pub field: String,
+    /// Rows that have the field.
+    pub present: usize,
+}
+
+/// Builds the report from the outputs and their verdicts under `contract`.
+pub fn coverage(contract: &Contract, outputs: &[Value], verdicts: &[Verdict]) -> Coverage {
+    let rules = metrics(verdicts).rules;
+    let never_violated = rules
+        .iter()
+        .filter(|rule| rule.failed_outputs == 0)
+        .cloned()
+        .collect();
+    let guarded: Vec<&str> = contract
+        .rule_specs()
+        .flat_map(|(_, spec)| spec.rule.referenced_fields())
+        .collect();
+    let unguarded_fields = profile(outputs)
+        .fields
+        .into_iter()
+        .filter(|(field, _)| !guarded.contains(&field.as_str()))
+        .map(|(field, profile)| UnguardedField {
+            field,
+            present: profile.present,
+        })
+        .collect();
+    Coverage {
+        outputs: outputs.len(),
+        rules,
+        never_violated,
+        unguarded_fields,
+    }
+}
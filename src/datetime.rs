@@ -0,0 +1,115 @@
+use chrono::format::{parse, Item, ParseErrorKind, Parsed, StrftimeItems};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone};
+
+use crate::contract::DatetimePreset;
+
+/// How a datetime string is expected to be written: a named preset or a
+/// chrono (strftime-style) format string.
+#[derive(Debug, Clone, Copy)]
+pub enum DatetimeFormat<'a> {
+    Preset(DatetimePreset),
+    Custom(&'a str),
+}
+
+impl DatetimeFormat<'_> {
+    pub fn label(&self) -> String {
+        match self {
+            DatetimeFormat::Preset(preset) => preset.name().to_string(),
+            DatetimeFormat::Custom(format) => format.to_string(),
+        }
+    }
+}
+
+impl DatetimePreset {
+    pub fn name(self) -> &'static str {
+        match self {
+            DatetimePreset::Rfc3339 => "rfc3339",
+            DatetimePreset::Rfc2822 => "rfc2822",
+            DatetimePreset::IsoDate => "iso_date",
+        }
+    }
+}
+
+/// Rejects format strings containing unknown or malformed specifiers.
+pub fn validate_format(format: &str) -> Result<(), String> {
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("Invalid datetime format '{format}'."));
+    }
+    Ok(())
+}
+
+/// Checks that `value` parses under `format` and describes a real calendar
+/// date and time, returning chrono's parse error message otherwise.
+pub fn check_datetime(value: &str, format: DatetimeFormat) -> Result<(), String> {
+    match format {
+        DatetimeFormat::Preset(DatetimePreset::Rfc3339) => DateTime::parse_from_rfc3339(value)
+            .map(drop)
+            .map_err(|err| err.to_string()),
+        DatetimeFormat::Preset(DatetimePreset::Rfc2822) => DateTime::parse_from_rfc2822(value)
+            .map(drop)
+            .map_err(|err| err.to_string()),
+        DatetimeFormat::Preset(DatetimePreset::IsoDate) => {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(drop)
+                .map_err(|err| err.to_string())
+        }
+        DatetimeFormat::Custom(format) => {
+            let mut parsed = Parsed::new();
+            parse(&mut parsed, value, StrftimeItems::new(format)).map_err(|err| err.to_string())?;
+            // `parse` only checks each field's range; resolving the date and time
+            // catches impossible combinations such as February 30. Formats that
+            // carry no date (or no time) at all report `NotEnough` and are fine.
+            for resolved in [
+                parsed.to_naive_date().map(drop),
+                parsed.to_naive_time().map(drop),
+            ] {
+                if let Err(err) = resolved {
+                    if err.kind() != ParseErrorKind::NotEnough {
+                        return Err(err.to_string());
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Parses a `timezone` setting: `UTC`, `Z` or a fixed offset such as `+02:00`.
+pub fn parse_offset(timezone: &str) -> Result<FixedOffset, String> {
+    if timezone.eq_ignore_ascii_case("utc") || timezone == "Z" {
+        return Ok(FixedOffset::east_opt(0).expect("zero offset is valid"));
+    }
+    timezone
+        .parse::<FixedOffset>()
+        .map_err(|_| format!("Invalid timezone offset '{timezone}'."))
+}
+
+/// Parses `value` into an instant. Without a `format` the value must be
+/// RFC 3339. With a format, values that carry no offset are interpreted in
+/// `assumed_offset`, and date-only values mean midnight.
+pub fn parse_instant(
+    value: &str,
+    format: Option<&str>,
+    assumed_offset: FixedOffset,
+) -> Result<DateTime<FixedOffset>, String> {
+    let Some(format) = format else {
+        return DateTime::parse_from_rfc3339(value).map_err(|err| err.to_string());
+    };
+
+    let mut parsed = Parsed::new();
+    parse(&mut parsed, value, StrftimeItems::new(format)).map_err(|err| err.to_string())?;
+    if let Ok(instant) = parsed.to_datetime() {
+        return Ok(instant);
+    }
+
+    let date = parsed.to_naive_date().map_err(|err| err.to_string())?;
+    let time = match parsed.to_naive_time() {
+        Ok(time) => time,
+        Err(err) if err.kind() == ParseErrorKind::NotEnough => NaiveTime::MIN,
+        Err(err) => return Err(err.to_string()),
+    };
+    assumed_offset
+        .from_local_datetime(&date.and_time(time))
+        .single()
+        .ok_or_else(|| "no possible date and time matching input".to_string())
+}
@@ -0,0 +1,149 @@
+//! Comparison of two saved verdicts, e.g. of one contract checked against the
+//! outputs of two model versions. Verdicts are compared in their printed JSON
+//! form, so any saved verdict or batch document can be diffed.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use serde_json::Value;
+
+// Violations are matched on these keys; anything else that differs makes
+// them "changed".
+const IDENTITY_KEYS: [&str; 4] = ["code", "rule", "field", "row"];
+// Keys that differ between any two files and say nothing about the model.
+const VOLATILE_KEYS: [&str; 2] = ["position", "end_position"];
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct VerdictDiff {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+    pub status_before: Value,
+    pub status_after: Value,
+    pub appeared: Vec<Value>,
+    pub disappeared: Vec<Value>,
+    pub changed: Vec<ChangedViolation>,
+    pub unchanged: usize,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ChangedViolation {
+    pub before: Value,
+    pub after: Value,
+}
+
+impl VerdictDiff {
+    /// Whether the newer verdict has violations the older one did not.
+    pub fn regressed(&self) -> bool {
+        !self.appeared.is_empty() || !self.changed.is_empty()
+    }
+}
+
+/// Diffs two verdicts. Batch documents are diffed verdict by verdict, in
+/// order; a verdict present on one side only counts as all-appeared or
+/// all-disappeared.
+pub fn diff_documents(before: &Value, after: &Value) -> Vec<VerdictDiff> {
+    let empty = Value::Null;
+    let before = verdicts(before);
+    let after = verdicts(after);
+    (0..before.len().max(after.len()))
+        .map(|idx| {
+            diff_verdicts(
+                before.get(idx).copied().unwrap_or(&empty),
+                after.get(idx).copied().unwrap_or(&empty),
+            )
+        })
+        .collect()
+}
+
+fn verdicts(document: &Value) -> Vec<&Value> {
+    match document.get("verdicts").and_then(Value::as_array) {
+        Some(verdicts) => verdicts.iter().collect(),
+        None => vec![document],
+    }
+}
+
+fn diff_verdicts(before: &Value, after: &Value) -> VerdictDiff {
+    let mut remaining: Vec<&Value> = violations(before);
+    let mut diff = VerdictDiff {
+        output: after.get("output").or(before.get("output")).cloned(),
+        status_before: before.get("status").cloned().unwrap_or(Value::Null),
+        status_after: after.get("status").cloned().unwrap_or(Value::Null),
+        appeared: Vec::new(),
+        disappeared: Vec::new(),
+        changed: Vec::new(),
+        unchanged: 0,
+    };
+    for violation in violations(after) {
+        let matched = remaining
+            .iter()
+            .position(|old| equivalent(old, violation))
+            .or_else(|| {
+                remaining
+                    .iter()
+                    .position(|old| same_identity(old, violation))
+            });
+        match matched.map(|idx| remaining.remove(idx)) {
+            Some(old) if equivalent(old, violation) => diff.unchanged += 1,
+            Some(old) => diff.changed.push(ChangedViolation {
+                before: old.clone(),
+                after: violation.clone(),
+            }),
+            None => diff.appeared.push(violation.clone()),
+        }
+    }
+    diff.disappeared = remaining.into_iter().cloned().collect();
+    diff
+}
+
+/// The violations of a printed verdict in either layout: top-level and, with
+/// `--group-by row`, nested under `rows`.
+pub fn violations(verdict: &Value) -> Vec<&Value> {
+    let top = verdict
+        .get("violations")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten();
+    let nested = verdict
+        .get("rows")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|row| row.get("violations").and_then(Value::as_array))
+        .flatten();
+    top.chain(nested).collect()
+}
+
+/// Distinct row indexes of printed violations, including those merged by
+/// `--dedupe` under `rows`.
+pub fn violation_rows<'a>(violations: impl IntoIterator<Item = &'a Value>) -> BTreeSet<u64> {
+    violations
+        .into_iter()
+        .flat_map(|violation| {
+            let merged = violation.get("rows").and_then(Value::as_array);
+            violation
+                .get("row")
+                .into_iter()
+                .chain(merged.into_iter().flatten())
+                .filter_map(Value::as_u64)
+        })
+        .collect()
+}
+
+fn equivalent(a: &Value, b: &Value) -> bool {
+    match (a.as_object(), b.as_object()) {
+        (Some(a), Some(b)) => {
+            let stable = |map: &serde_json::Map<String, Value>| {
+                map.iter()
+                    .filter(|(key, _)| !VOLATILE_KEYS.contains(&key.as_str()))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect::<serde_json::Map<_, _>>()
+            };
+            stable(a) == stable(b)
+        }
+        _ => a == b,
+    }
+}
+
+fn same_identity(a: &Value, b: &Value) -> bool {
+    IDENTITY_KEYS.iter().all(|key| a.get(key) == b.get(key))
+}
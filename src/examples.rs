@@ -0,0 +1,154 @@
+use std::fmt::Write;
+
+use chrono::format::StrftimeItems;
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone};
+use regex::Regex;
+use regex_syntax::hir::{Class, ClassUnicode, Hir, HirKind, Literal};
+use serde_json::{json, Value};
+
+use crate::contract::{CheckDigitScheme, Contract, DatetimePreset, Rule, ValueType};
+use crate::datetime::{check_datetime, DatetimeFormat};
+
+// Characters tried first when picking a member of a regex class, so examples
+// read like plausible values rather than control characters.
+const PREFERRED_CHARS: [char; 5] = ['a', 'A', '0', '-', ' '];
+
+/// A minimal value that satisfies `rule` for the field it checks, or `None`
+/// when the rule has no single-field example (output-level and cross-output
+/// rules, combinators) or none could be derived.
+pub fn rule_example(contract: &Contract, rule: &Rule) -> Option<Value> {
+    match rule {
+        Rule::RequiredField { default, .. } => default.clone(),
+        Rule::FieldType { expected, .. } => Some(type_example(expected)),
+        Rule::AllowedValues { values, .. } => values.first().cloned(),
+        Rule::Const { value, .. } => Some(value.clone()),
+        Rule::Regex {
+            pattern,
+            pattern_ref,
+            ..
+        } => {
+            let pattern = contract.resolve_pattern(pattern.as_deref(), pattern_ref.as_deref())?;
+            regex_example(pattern).map(Value::String)
+        }
+        Rule::EmbeddedJson { expected, .. } => {
+            Some(Value::String(type_example(expected).to_string()))
+        }
+        Rule::CheckDigit { scheme, .. } => Some(Value::String(
+            match scheme {
+                CheckDigitScheme::Luhn => "00",
+                CheckDigitScheme::Isbn10 => "0000000000",
+                CheckDigitScheme::Isbn13 => "9780000000002",
+                CheckDigitScheme::Ean => "00000000",
+            }
+            .to_string(),
+        )),
+        Rule::FieldArrayLength { min, .. } => {
+            let len = usize::try_from(min.unwrap_or(0)).ok()?;
+            Some(Value::Array(vec![Value::Null; len]))
+        }
+        Rule::NumberRange { min, max, .. } => Some(json!(min.or(*max).unwrap_or(0.0))),
+        Rule::StringLength { min, .. } => {
+            let len = usize::try_from(min.unwrap_or(0)).ok()?;
+            Some(Value::String("a".repeat(len)))
+        }
+        Rule::ApproxEqual {
+            target: Some(target),
+            ..
+        } => Some(json!(target)),
+        Rule::DatetimeFormat { format, preset, .. } => {
+            let format = match (format, preset) {
+                (Some(format), _) => DatetimeFormat::Custom(format),
+                (None, Some(preset)) => DatetimeFormat::Preset(*preset),
+                (None, None) => return None,
+            };
+            datetime_example(format).map(Value::String)
+        }
+        _ => None,
+    }
+}
+
+fn type_example(value_type: &ValueType) -> Value {
+    match value_type {
+        ValueType::String => json!(""),
+        ValueType::Number => json!(0),
+        ValueType::Boolean => json!(false),
+        ValueType::Object => json!({}),
+        ValueType::Array => json!([]),
+        ValueType::Null => Value::Null,
+    }
+}
+
+/// The shortest string the pattern's syntax tree produces: minimum
+/// repetitions, first alternatives, one member per class. Patterns whose
+/// assertions reject that string (e.g. `\b` between two non-word characters)
+/// get no example.
+fn regex_example(pattern: &str) -> Option<String> {
+    let hir = regex_syntax::Parser::new().parse(pattern).ok()?;
+    let mut example = String::new();
+    write_hir(&hir, &mut example)?;
+    Regex::new(pattern)
+        .ok()?
+        .is_match(&example)
+        .then_some(example)
+}
+
+fn write_hir(hir: &Hir, out: &mut String) -> Option<()> {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => {}
+        HirKind::Literal(Literal(bytes)) => out.push_str(std::str::from_utf8(bytes).ok()?),
+        HirKind::Class(Class::Unicode(class)) => out.push(class_member(class)?),
+        HirKind::Class(Class::Bytes(class)) => {
+            let byte = class.ranges().first()?.start();
+            out.push(char::from(byte).is_ascii().then_some(char::from(byte))?);
+        }
+        HirKind::Repetition(repetition) => {
+            for _ in 0..repetition.min {
+                write_hir(&repetition.sub, out)?;
+            }
+        }
+        HirKind::Capture(capture) => write_hir(&capture.sub, out)?,
+        HirKind::Concat(parts) => {
+            for part in parts {
+                write_hir(part, out)?;
+            }
+        }
+        HirKind::Alternation(alternatives) => write_hir(alternatives.first()?, out)?,
+    }
+    Some(())
+}
+
+fn class_member(class: &ClassUnicode) -> Option<char> {
+    let ranges = class.ranges();
+    PREFERRED_CHARS
+        .into_iter()
+        .find(|&c| {
+            ranges
+                .iter()
+                .any(|range| range.start() <= c && c <= range.end())
+        })
+        .or_else(|| ranges.first().map(|range| range.start()))
+}
+
+// Renders 2000-01-01 00:00:00 UTC in `format`, keeping it only if it parses
+// back (formats may omit fields the parser needs).
+fn datetime_example(format: DatetimeFormat) -> Option<String> {
+    let instant: DateTime<FixedOffset> = FixedOffset::east_opt(0)?
+        .from_local_datetime(&NaiveDate::from_ymd_opt(2000, 1, 1)?.and_hms_opt(0, 0, 0)?)
+        .single()?;
+    let example = match format {
+        DatetimeFormat::Preset(DatetimePreset::Rfc3339) => instant.to_rfc3339(),
+        DatetimeFormat::Preset(DatetimePreset::Rfc2822) => instant.to_rfc2822(),
+        DatetimeFormat::Preset(DatetimePreset::IsoDate) => instant.format("%Y-%m-%d").to_string(),
+        DatetimeFormat::Custom(format) => {
+            let mut example = String::new();
+            write!(
+                example,
+                "{}",
+                instant.format_with_items(StrftimeItems::new(format))
+            )
+            .ok()?;
+            example
+        }
+    };
+    check_datetime(&example, format).ok().map(|_| example)
+}
@@ -0,0 +1,249 @@
+//! Plain-language summaries of saved verdicts, grouped by root cause, for
+//! alerts and human triage.
+
+use serde_json::Value;
+
+use llmc::codes;
+
+use crate::diff::{violation_rows, violations};
+
+// Distinct offending values listed per root cause before eliding the rest.
+const MAX_LISTED_VALUES: usize = 5;
+
+/// One line per verdict status plus one line per root cause. Batch documents
+/// get a block per verdict, headed by its output.
+pub fn explain(document: &Value) -> String {
+    match document.get("verdicts").and_then(Value::as_array) {
+        Some(verdicts) => verdicts
+            .iter()
+            .map(|verdict| {
+                let output = verdict
+                    .get("output")
+                    .and_then(Value::as_str)
+                    .unwrap_or("output");
+                format!("{output}\n{}", explain_verdict(verdict))
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        None => explain_verdict(document),
+    }
+}
+
+// Violations sharing code, rule and field, which typically have one cause.
+struct RootCause<'a> {
+    code: &'a str,
+    rule: &'a str,
+    field: &'a str,
+    violations: Vec<&'a Value>,
+}
+
+fn explain_verdict(verdict: &Value) -> String {
+    let violations = violations(verdict);
+    let status = verdict
+        .get("status")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    let mut causes: Vec<RootCause> = Vec::new();
+    for violation in &violations {
+        let text = |key: &str| violation.get(key).and_then(Value::as_str).unwrap_or("");
+        let (code, rule, field) = (text("code"), text("rule"), text("field"));
+        match causes
+            .iter_mut()
+            .find(|cause| (cause.code, cause.rule, cause.field) == (code, rule, field))
+        {
+            Some(cause) => cause.violations.push(violation),
+            None => causes.push(RootCause {
+                code,
+                rule,
+                field,
+                violations: vec![violation],
+            }),
+        }
+    }
+
+    let mut lines = vec![match causes.len() {
+        0 => format!("{}: no violations.", status.to_uppercase()),
+        count => format!(
+            "{}: {} in {}.",
+            status.to_uppercase(),
+            plural(violations.len(), "violation"),
+            plural(count, "root cause")
+        ),
+    }];
+    lines.extend(causes.iter().map(|cause| format!("- {}", sentence(cause))));
+    lines.join("\n")
+}
+
+fn sentence(cause: &RootCause) -> String {
+    let first = cause.violations[0];
+    let field = cause.field;
+    let place = place(&cause.violations);
+    match cause.code {
+        codes::MISSING_FIELD | codes::MISSING_TYPED_FIELD => {
+            format!("'{field}' is missing {place}.")
+        }
+        codes::UNKNOWN_FIELD => format!("'{field}' is not part of the contract {place}."),
+        codes::TYPE_MISMATCH => format!(
+            "'{field}' has the wrong type {place} (expected {}).",
+            render(first.get("expected"))
+        ),
+        codes::UNSUITABLE_VALUE_TYPE => {
+            format!(
+                "'{field}' holds values the {} rule cannot check {place}.",
+                cause.rule
+            )
+        }
+        codes::DISALLOWED_VALUE => {
+            let expected = first.get("expected");
+            let allowed = match expected.and_then(|expected| expected.get("values_file")) {
+                Some(values_file) => format!("the values of {values_file}"),
+                None => format!(
+                    "{{{}}}",
+                    expected
+                        .and_then(Value::as_array)
+                        .map(|values| list(values.iter()))
+                        .unwrap_or_default()
+                ),
+            };
+            format!(
+                "'{field}' uses values outside {allowed} {place}{}.",
+                seen(&cause.violations)
+            )
+        }
+        codes::CONST_MISMATCH => format!(
+            "'{field}' is not {} {place}{}.",
+            render(first.get("expected")),
+            seen(&cause.violations)
+        ),
+        codes::PATTERN_MISMATCH => format!(
+            "'{field}' does not match {} {place}{}.",
+            render(first.get("expected")),
+            seen(&cause.violations)
+        ),
+        codes::VALUES_NOT_COVERED => format!(
+            "No row uses {} for '{field}'.",
+            first
+                .get("actual")
+                .and_then(Value::as_array)
+                .map(|values| list(values.iter()))
+                .unwrap_or_default()
+        ),
+        codes::INVALID_EMBEDDED_JSON => format!("'{field}' is not valid embedded JSON {place}."),
+        codes::INVALID_CHECK_DIGIT => format!("'{field}' has an invalid check digit {place}."),
+        codes::INVALID_COORDINATE => format!("'{field}' has invalid coordinates {place}."),
+        codes::INVALID_DATETIME => format!(
+            "'{field}' is not a datetime in format {} {place}.",
+            render(first.get("expected"))
+        ),
+        codes::INVALID_LOCALE_NUMBER => {
+            format!("'{field}' is not a correctly formatted number {place}.")
+        }
+        codes::TOO_FEW_ITEMS => format!(
+            "The output has {} items, fewer than the required {}.",
+            render(first.get("actual")),
+            render(first.get("expected"))
+        ),
+        codes::NUMBER_OUT_OF_RANGE => format!(
+            "'{field}' is outside {} {place}{}.",
+            render(first.get("expected")),
+            seen(&cause.violations)
+        ),
+        codes::STRING_LENGTH => format!("'{field}' has a length out of range {place}."),
+        codes::TOO_MANY_ITEMS => format!(
+            "The output has {} items, more than the allowed {}.",
+            render(first.get("actual")),
+            render(first.get("expected"))
+        ),
+        codes::DUPLICATE_VALUE => format!("'{field}' repeats values {place}."),
+        codes::COMPARISON_FAILED => format!(
+            "'{field}' is not {} {place}.",
+            render(first.get("expected"))
+        ),
+        codes::EMPTY_ROW => format!("{} empty.", place_subject(&cause.violations)),
+        codes::ARRAY_LENGTH => format!("'{field}' has an array length out of range {place}."),
+        codes::KEY_ORDER => format!(
+            "Keys are not in the order {} {place}.",
+            render(first.get("expected"))
+        ),
+        codes::NOT_APPROX_EQUAL => format!("'{field}' is not close enough to its target {place}."),
+        codes::DATETIME_ORDER => format!("'{field}' is out of order {place}."),
+        codes::DUPLICATE_ACROSS_OUTPUTS => format!("'{field}' repeats values {place}."),
+        codes::INCONSISTENT_ACROSS_OUTPUTS => {
+            format!("'{field}' disagrees between rows sharing a key {place}.")
+        }
+        codes::NO_MATCHING_GROUP | codes::MULTIPLE_MATCHING_GROUPS | codes::NEGATED_RULES_MATCH => {
+            format!("The {} rule is not satisfied {place}.", cause.rule)
+        }
+        _ => {
+            let message = first.get("message").and_then(Value::as_str).unwrap_or("");
+            match cause.violations.len() {
+                1 => message.to_string(),
+                count => format!("{message} ({count} times)"),
+            }
+        }
+    }
+}
+
+// "in 3 rows" for row violations, "in the output" otherwise.
+fn place(violations: &[&Value]) -> String {
+    match violation_rows(violations.iter().copied()).len() {
+        0 => "in the output".to_string(),
+        count => format!("in {}", plural(count, "row")),
+    }
+}
+
+// "3 rows are" / "1 row is", for causes that describe whole rows.
+fn place_subject(violations: &[&Value]) -> String {
+    match violation_rows(violations.iter().copied()).len() {
+        1 => "1 row is".to_string(),
+        count => format!("{count} rows are"),
+    }
+}
+
+// " (seen: a, b)" listing the distinct offending values.
+fn seen(violations: &[&Value]) -> String {
+    let mut values: Vec<&Value> = Vec::new();
+    for actual in violations
+        .iter()
+        .filter_map(|violation| violation.get("actual"))
+    {
+        if !values.contains(&actual) {
+            values.push(actual);
+        }
+    }
+    if values.is_empty() {
+        String::new()
+    } else {
+        format!(" (seen: {})", list(values.into_iter()))
+    }
+}
+
+fn list<'a>(values: impl Iterator<Item = &'a Value>) -> String {
+    let values: Vec<&Value> = values.collect();
+    let mut listed: Vec<String> = values
+        .iter()
+        .take(MAX_LISTED_VALUES)
+        .map(|value| render(Some(value)))
+        .collect();
+    if values.len() > MAX_LISTED_VALUES {
+        listed.push(format!("{} more", values.len() - MAX_LISTED_VALUES));
+    }
+    listed.join(", ")
+}
+
+// Strings without their JSON quotes, everything else as JSON.
+fn render(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(text)) => text.clone(),
+        Some(value) => value.to_string(),
+        None => "?".to_string(),
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
@@ -0,0 +1,121 @@
+//! Lenient parsing of raw model text: the JSON object or array inside a
+//! fenced code block, or surrounded by prose such as "Here is the result:".
+
+use serde::Serialize;
+use serde_json::{Deserializer, Value};
+
+use crate::spans::{position_at, Position};
+
+/// Where the JSON of a text output was found, when it had to be extracted.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Extraction {
+    pub method: ExtractionMethod,
+    /// Where the JSON starts in the text.
+    pub position: Position,
+    /// Just past the end of the JSON.
+    pub end_position: Position,
+    /// Informational note for the verdict, e.g. `JSON extracted from a fenced
+    /// code block at line 3.`
+    pub note: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionMethod {
+    /// The content of a ```` ``` ```` or `~~~` fenced code block.
+    FencedBlock,
+    /// A value embedded in other text.
+    Embedded,
+}
+
+/// The JSON object or array in `text`: the content of the first fenced code
+/// block holding one, else the largest one embedded in the text (the first
+/// of equally large ones).
+pub fn extract_json(text: &str) -> Option<(Value, Extraction)> {
+    if let Some((start, end, value)) = fenced_block(text) {
+        return Some((
+            value,
+            extraction(text, ExtractionMethod::FencedBlock, start, end),
+        ));
+    }
+    let (start, end, value) = largest_embedded(text)?;
+    Some((
+        value,
+        extraction(text, ExtractionMethod::Embedded, start, end),
+    ))
+}
+
+fn extraction(text: &str, method: ExtractionMethod, start: usize, end: usize) -> Extraction {
+    let position = position_at(text, start);
+    let note = match method {
+        ExtractionMethod::FencedBlock => format!(
+            "JSON extracted from a fenced code block at line {}.",
+            position.line
+        ),
+        ExtractionMethod::Embedded => format!(
+            "JSON extracted from line {}, column {}; the surrounding text was ignored.",
+            position.line, position.column
+        ),
+    };
+    Extraction {
+        method,
+        position,
+        end_position: position_at(text, end),
+        note,
+    }
+}
+
+// Byte range and value of the first fenced block whose content is an object
+// or array. Blocks are delimited by lines starting with three backticks or
+// tildes, the opening one optionally followed by a language such as `json`.
+fn fenced_block(text: &str) -> Option<(usize, usize, Value)> {
+    let mut opened: Option<(&str, usize)> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let fence = ["```", "~~~"]
+            .into_iter()
+            .find(|fence| line.trim_start().starts_with(fence));
+        match (opened, fence) {
+            (None, Some(fence)) => opened = Some((fence, offset + line.len())),
+            (Some((open, content_start)), Some(fence)) if fence == open => {
+                opened = None;
+                let content = &text[content_start..offset];
+                let trimmed = content.trim();
+                if let Ok(value @ (Value::Object(_) | Value::Array(_))) =
+                    serde_json::from_str(trimmed)
+                {
+                    let start = content_start + (content.len() - content.trim_start().len());
+                    return Some((start, start + trimmed.len(), value));
+                }
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+    None
+}
+
+// Byte range and value of the largest object or array in `text`, trying each
+// `{` or `[` outside values already found.
+fn largest_embedded(text: &str) -> Option<(usize, usize, Value)> {
+    let mut largest: Option<(usize, usize, Value)> = None;
+    let mut idx = 0;
+    while let Some(found) = text[idx..].find(['{', '[']) {
+        let start = idx + found;
+        let mut values = Deserializer::from_str(&text[start..]).into_iter::<Value>();
+        match values.next() {
+            Some(Ok(value)) => {
+                let end = start + values.byte_offset();
+                if largest
+                    .as_ref()
+                    .is_none_or(|(from, to, _)| end - start > to - from)
+                {
+                    largest = Some((start, end, value));
+                }
+                idx = end;
+            }
+            _ => idx = start + 1,
+        }
+    }
+    largest
+}
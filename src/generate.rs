@@ -0,0 +1,173 @@
+//! Random outputs that satisfy a contract, for load-testing consumers and for
+//! smoke-testing contracts themselves.
+
+use std::collections::BTreeMap;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde_json::{json, Map, Value};
+
+use llmc::contract::{Contract, OutputType, Rule, ValueType};
+use llmc::examples::rule_example;
+
+// Upper bound on regex repetitions, so `.*` and friends stay short.
+const MAX_REGEX_REPEAT: u32 = 8;
+// Rows added on top of the contract's `min_items`.
+const EXTRA_ROWS: u64 = 4;
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// One random output of the contract's `output_type` whose rows hold every
+/// field the contract's rules name. Each field's value comes from the most
+/// constraining rule on it: `const`, then `allowed_values`, `regex`,
+/// `number_range`, `string_length`, `field_type`, `field_array_length` and
+/// finally the rule's example. Rows list the keys of a `key_order` rule
+/// first, in its order. Output-level and cross-row rules other than
+/// `min_items` and `max_items` are not considered, so verify the result when
+/// the contract relies on them.
+pub fn generate(contract: &Contract, rng: &mut impl Rng) -> Value {
+    let mut fields: BTreeMap<&str, Vec<&Rule>> = BTreeMap::new();
+    let mut min_items = 1;
+    let mut max_items = u64::MAX;
+    let mut order: &[String] = &[];
+    for (_, spec) in contract.rule_specs() {
+        match &spec.rule {
+            Rule::MinItems { value } => min_items = min_items.max(*value),
+            Rule::MaxItems { value } => max_items = max_items.min(*value),
+            Rule::KeyOrder { keys, .. } => {
+                order = keys;
+                for key in keys {
+                    fields.entry(key).or_default().push(&spec.rule);
+                }
+            }
+            rule => {
+                if let Some(field) = rule.field() {
+                    fields.entry(field).or_default().push(rule);
+                }
+            }
+        }
+    }
+
+    let rows = match contract.output_type {
+        OutputType::Object => None,
+        OutputType::Array => {
+            let most = (min_items + EXTRA_ROWS).min(max_items);
+            Some(rng.gen_range(min_items.min(most)..=most))
+        }
+    };
+    // Keys named by `key_order` come first, in its order.
+    let mut names: Vec<&str> = fields.keys().copied().collect();
+    names.sort_by_key(|name| {
+        order
+            .iter()
+            .position(|key| key == name)
+            .unwrap_or(order.len())
+    });
+    let mut row = || -> Value {
+        let row: Map<String, Value> = names
+            .iter()
+            .map(|field| {
+                (
+                    field.to_string(),
+                    field_value(contract, &fields[field], rng),
+                )
+            })
+            .collect();
+        Value::Object(row)
+    };
+    match rows {
+        None => row(),
+        Some(rows) => Value::Array((0..rows).map(|_| row()).collect()),
+    }
+}
+
+fn field_value(contract: &Contract, rules: &[&Rule], rng: &mut impl Rng) -> Value {
+    let find = |matches: fn(&Rule) -> bool| rules.iter().copied().find(|rule| matches(rule));
+
+    if let Some(Rule::Const { value, .. }) = find(|rule| matches!(rule, Rule::Const { .. })) {
+        return value.clone();
+    }
+    if let Some(Rule::AllowedValues { values, .. }) =
+        find(|rule| matches!(rule, Rule::AllowedValues { values, .. } if !values.is_empty()))
+    {
+        return values.choose(rng).cloned().unwrap_or(Value::Null);
+    }
+    if let Some(Rule::Regex {
+        pattern,
+        pattern_ref,
+        ..
+    }) = find(|rule| matches!(rule, Rule::Regex { .. }))
+    {
+        let generated = contract
+            .resolve_pattern(pattern.as_deref(), pattern_ref.as_deref())
+            .and_then(|pattern| rand_regex::Regex::compile(pattern, MAX_REGEX_REPEAT).ok())
+            .map(|regex| rng.sample::<String, _>(&regex));
+        if let Some(generated) = generated {
+            return Value::String(generated);
+        }
+    }
+    if let Some(Rule::NumberRange { min, max, .. }) =
+        find(|rule| matches!(rule, Rule::NumberRange { .. }))
+    {
+        let low = min.unwrap_or_else(|| max.unwrap_or(0.0) - 1000.0);
+        let high = max.unwrap_or(low + 1000.0).max(low);
+        // Whole numbers read better, when the range holds any.
+        return if low.ceil() <= high.floor() {
+            json!(rng.gen_range(low.ceil() as i64..=high.floor() as i64))
+        } else {
+            json!(rng.gen_range(low..=high))
+        };
+    }
+    if let Some(Rule::StringLength { min, max, .. }) =
+        find(|rule| matches!(rule, Rule::StringLength { .. }))
+    {
+        let min = min.unwrap_or(0);
+        let len = rng.gen_range(min..=max.unwrap_or(min + 12).max(min));
+        return random_text(rng, len);
+    }
+    if let Some(Rule::FieldType { expected, .. }) =
+        find(|rule| matches!(rule, Rule::FieldType { .. }))
+    {
+        if *expected != ValueType::Array {
+            return typed_value(expected, rng);
+        }
+    }
+    if let Some(Rule::FieldArrayLength { min, max, .. }) =
+        find(|rule| matches!(rule, Rule::FieldArrayLength { .. }))
+    {
+        let min = min.unwrap_or(0);
+        let len = rng.gen_range(min..=max.unwrap_or(min + EXTRA_ROWS).max(min));
+        return Value::Array((0..len).map(|_| random_string(rng)).collect());
+    }
+    // A required field's default satisfies nothing but its presence.
+    let (required, others): (Vec<&Rule>, Vec<&Rule>) = rules
+        .iter()
+        .partition(|rule| matches!(rule, Rule::RequiredField { .. }));
+    others
+        .into_iter()
+        .chain(required)
+        .find_map(|rule| rule_example(contract, rule))
+        .unwrap_or_else(|| random_string(rng))
+}
+
+fn typed_value(value_type: &ValueType, rng: &mut impl Rng) -> Value {
+    match value_type {
+        ValueType::String => random_string(rng),
+        ValueType::Number => json!(rng.gen_range(0..1000)),
+        ValueType::Boolean => json!(rng.gen::<bool>()),
+        ValueType::Object => json!({}),
+        ValueType::Array => json!([]),
+        ValueType::Null => Value::Null,
+    }
+}
+
+fn random_string(rng: &mut impl Rng) -> Value {
+    let len = rng.gen_range(1..=12);
+    random_text(rng, len)
+}
+
+fn random_text(rng: &mut impl Rng, len: u64) -> Value {
+    let text: String = (0..len)
+        .map(|_| char::from(*ALPHABET.choose(rng).expect("alphabet is not empty")))
+        .collect();
+    Value::String(text)
+}
@@ -0,0 +1,190 @@
+use serde_json::Value;
+
+use crate::contract::{Contract, Rule, ValueType};
+
+// Lists longer than this are cut short in hints.
+const MAX_LISTED_VALUES: usize = 10;
+
+/// A one-sentence instruction for satisfying `rule`, suitable for pasting
+/// into a correction prompt. Contracts can override it per rule with `hint`.
+pub fn rule_hint(contract: &Contract, rule: &Rule) -> String {
+    match rule {
+        Rule::RequiredField {
+            field,
+            default: Some(default),
+        } => format!("Include field '{field}', e.g. {default}."),
+        Rule::RequiredField { field, .. } => format!("Include field '{field}'."),
+        Rule::FieldType { field, expected } => {
+            format!("Field '{field}' must be {}.", type_phrase(expected))
+        }
+        Rule::AllowedValues { field, values, .. } => {
+            format!("Field '{field}' must be one of {}.", list_values(values))
+        }
+        Rule::CoversValues { field, values, .. } => format!(
+            "Include at least one row with '{field}' set to each of {}.",
+            list_values(values)
+        ),
+        Rule::Const { field, value, .. } => format!("Field '{field}' must equal {value}."),
+        Rule::Regex {
+            field,
+            pattern,
+            pattern_ref,
+        } => {
+            let pattern = contract
+                .resolve_pattern(pattern.as_deref(), pattern_ref.as_deref())
+                .unwrap_or_default();
+            format!("Field '{field}' must be a string matching {pattern}.")
+        }
+        Rule::MinItems { value } => format!("Return an array of at least {value} items."),
+        Rule::MaxItems { value } => format!("Return an array of at most {value} items."),
+        Rule::NoEmptyRows => "Give every row at least one non-empty value.".to_string(),
+        Rule::KeyOrder { keys, exact } => {
+            let keys: Vec<String> = keys.iter().map(|key| format!("'{key}'")).collect();
+            if *exact {
+                format!(
+                    "Give every object exactly the keys {}, in that order.",
+                    keys.join(", ")
+                )
+            } else {
+                format!("Write the keys {} in that order.", keys.join(", "))
+            }
+        }
+        Rule::EmbeddedJson { field, expected } => format!(
+            "Field '{field}' must be a string holding serialized JSON {}.",
+            type_phrase(expected)
+        ),
+        Rule::CheckDigit { field, scheme } => format!(
+            "Field '{field}' must be a {} code with a correct check digit.",
+            scheme.name()
+        ),
+        Rule::GeoCoordinate { bbox, .. } => {
+            let location = rule.field().unwrap_or_default();
+            match bbox {
+                Some(bbox) => format!(
+                    "Coordinates at '{location}' must lie within latitude {}..{} and longitude {}..{}.",
+                    bbox.min_lat, bbox.max_lat, bbox.min_lon, bbox.max_lon
+                ),
+                None => format!(
+                    "Coordinates at '{location}' must be numbers with latitude in -90..90 and longitude in -180..180."
+                ),
+            }
+        }
+        Rule::FieldArrayLength { field, min, max } => match (min, max) {
+            (Some(min), Some(max)) => {
+                format!("Field '{field}' must be an array of {min} to {max} items.")
+            }
+            (Some(min), None) => {
+                format!("Field '{field}' must be an array of at least {min} items.")
+            }
+            (None, Some(max)) => {
+                format!("Field '{field}' must be an array of at most {max} items.")
+            }
+            (None, None) => format!("Field '{field}' must be an array."),
+        },
+        Rule::NumberRange { field, min, max } => match (min, max) {
+            (Some(min), Some(max)) => {
+                format!("Field '{field}' must be a number from {min} to {max}.")
+            }
+            (Some(min), None) => format!("Field '{field}' must be a number of at least {min}."),
+            (None, Some(max)) => format!("Field '{field}' must be a number of at most {max}."),
+            (None, None) => format!("Field '{field}' must be a number."),
+        },
+        Rule::StringLength { field, min, max } => match (min, max) {
+            (Some(min), Some(max)) => {
+                format!("Field '{field}' must be a string of {min} to {max} characters.")
+            }
+            (Some(min), None) => {
+                format!("Field '{field}' must be a string of at least {min} characters.")
+            }
+            (None, Some(max)) => {
+                format!("Field '{field}' must be a string of at most {max} characters.")
+            }
+            (None, None) => format!("Field '{field}' must be a string."),
+        },
+        Rule::ApproxEqual {
+            field,
+            target,
+            other_field,
+            ..
+        } => match (target, other_field) {
+            (Some(target), _) => format!("Field '{field}' must be a number close to {target}."),
+            (None, Some(other)) => {
+                format!("Field '{field}' must be a number close to field '{other}'.")
+            }
+            (None, None) => format!("Field '{field}' must be a number."),
+        },
+        Rule::DatetimeFormat {
+            field,
+            format,
+            preset,
+        } => {
+            let format = format
+                .as_deref()
+                .or_else(|| preset.map(|preset| preset.name()))
+                .unwrap_or_default();
+            format!("Field '{field}' must be a datetime string in format {format}.")
+        }
+        Rule::DatetimeOrder {
+            field,
+            other_field,
+            operator,
+            ..
+        } => format!(
+            "Field '{field}' must be a datetime {} field '{other_field}'.",
+            operator.symbol()
+        ),
+        Rule::FieldComparison {
+            field,
+            operator,
+            other_field,
+        } => format!(
+            "Field '{field}' must be {} field '{other_field}'.",
+            operator.symbol()
+        ),
+        Rule::LocaleNumber { field, locale, .. } => {
+            format!("Field '{field}' must be a number string formatted for locale '{locale}'.")
+        }
+        Rule::UniqueValues { field, .. } => {
+            format!("Use a different value for '{field}' in every row.")
+        }
+        Rule::UniqueAcrossOutputs { field, .. } => {
+            format!("Use a value for '{field}' that no other row or output uses.")
+        }
+        Rule::ConsistentAcrossOutputs { key, fields, .. } => format!(
+            "Rows with the same '{key}' must agree on {}.",
+            fields
+                .iter()
+                .map(|field| format!("'{field}'"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Rule::AnyOf { .. } => "Satisfy all rules of at least one any_of group.".to_string(),
+        Rule::OneOf { .. } => "Satisfy all rules of exactly one one_of group.".to_string(),
+        Rule::Not { .. } => "Do not satisfy the rules negated by not.".to_string(),
+    }
+}
+
+fn type_phrase(value_type: &ValueType) -> &'static str {
+    match value_type {
+        ValueType::String => "a string",
+        ValueType::Number => "a number",
+        ValueType::Boolean => "a boolean",
+        ValueType::Object => "an object",
+        ValueType::Array => "an array",
+        ValueType::Null => "null",
+    }
+}
+
+fn list_values(values: &[Value]) -> String {
+    let listed: Vec<String> = values
+        .iter()
+        .take(MAX_LISTED_VALUES)
+        .map(Value::to_string)
+        .collect();
+    let more = values.len().saturating_sub(MAX_LISTED_VALUES);
+    if more > 0 {
+        format!("{}, ... ({more} more)", listed.join(", "))
+    } else {
+        listed.join(", ")
+    }
+}
@@ -0,0 +1,28 @@
+//! Contract verification for LLM outputs. The `llmc` binary is a thin CLI
+//! over this crate; Rust consumers can load contracts and verify values
+//! directly, snapshot-test verdicts, and with the `proptest` feature
+//! generate values a contract permits. The types and functions most
+//! embedders need are re-exported at the crate root.
+
+mod checksum;
+pub mod codes;
+pub mod contract;
+mod datetime;
+pub mod examples;
+pub mod extract;
+mod hints;
+mod messages;
+mod normalize;
+mod numbers;
+mod patterns;
+pub mod snapshot;
+pub mod spans;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+pub mod verifier;
+
+pub use contract::{Contract, Rule};
+pub use verifier::{
+    verify as verify_value, verify_batch, CompiledContract, RunError, Verdict, VerdictStatus,
+    VerifyOptions, Violation,
+};
@@ -0,0 +1,102 @@
+//! Verdicts as Language Server Protocol diagnostics
+//! (`PublishDiagnosticsParams`), so editor extensions can underline the
+//! offending values of recorded outputs.
+
+use std::fs;
+use std::path::{self, Path};
+
+use serde_json::{json, Value};
+
+use llmc::contract::Severity;
+use llmc::spans::Position;
+use llmc::verifier::Verdict;
+
+const SOURCE: &str = "llmc";
+
+/// `PublishDiagnosticsParams` for the file at `path`, with the violations of
+/// every verdict about it: one for an output file, one per record for a JSONL
+/// file. Clients replace a file's diagnostics on each publish, so a file must
+/// not be split across several. Violations without a position (output-level
+/// ones) cover the start of the file.
+pub fn publish_diagnostics(path: &Path, verdicts: &[&Verdict]) -> Value {
+    // Ranges count UTF-16 code units, which needs the line text; without
+    // the file, fall back to the character columns.
+    let source = fs::read_to_string(path).ok();
+    let diagnostics: Vec<Value> = verdicts
+        .iter()
+        .flat_map(|verdict| &verdict.violations)
+        .map(|violation| {
+            let start = violation.position.map_or_else(
+                || json!({"line": 0, "character": 0}),
+                |position| lsp_position(source.as_deref(), &position),
+            );
+            let end = violation.end_position.map_or_else(
+                || start.clone(),
+                |position| lsp_position(source.as_deref(), &position),
+            );
+            let mut diagnostic = json!({
+                "range": {"start": start, "end": end},
+                "severity": match violation.severity {
+                    Severity::Error => 1,
+                    Severity::Warning => 2,
+                    Severity::Info => 3,
+                },
+                "code": violation.code,
+                "source": SOURCE,
+                "message": violation.detail,
+            });
+            let mut data = serde_json::Map::new();
+            for (key, value) in [
+                ("rule", violation.rule.as_ref()),
+                ("field", violation.field.as_ref()),
+            ] {
+                if let Some(value) = value {
+                    data.insert(key.to_string(), json!(value));
+                }
+            }
+            if let Some(row) = violation.row {
+                data.insert("row".to_string(), json!(row));
+            }
+            if !data.is_empty() {
+                diagnostic["data"] = Value::Object(data);
+            }
+            diagnostic
+        })
+        .collect();
+    json!({ "uri": file_uri(path), "diagnostics": diagnostics })
+}
+
+// 0-based line and UTF-16 character.
+fn lsp_position(source: Option<&str>, position: &Position) -> Value {
+    let character = match source.and_then(|source| source.get(..position.offset)) {
+        Some(before) => {
+            let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+            before[line_start..].encode_utf16().count()
+        }
+        None => position.column - 1,
+    };
+    json!({"line": position.line - 1, "character": character})
+}
+
+/// `file://` URI of the absolute path.
+pub fn file_uri(path: &Path) -> String {
+    let absolute = path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let text = absolute.to_string_lossy().replace('\\', "/");
+    let separator = if text.starts_with('/') { "" } else { "/" };
+    format!("file://{separator}{}", encode_path(&text))
+}
+
+/// Percent-encodes all but unreserved characters and separators of a
+/// `/`-separated path.
+pub fn encode_path(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(char::from(byte))
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
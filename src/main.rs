@@ -1,38 +1,773 @@
-mod contract;
-mod verifier;
+mod batch;
+mod coverage;
+mod diff;
+mod explain;
+mod generate;
+mod lsp;
+mod meta;
+mod metrics;
+mod mutate;
+mod profile;
+mod remediate;
+mod report;
+#[cfg(feature = "tui")]
+mod tui;
 
-use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
-use clap::Parser;
+use chrono::Utc;
+use clap::{Parser, Subcommand, ValueEnum};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use serde_json::{json, Value};
 
-use verifier::{run, RunError, Verdict, VerdictStatus, Violation};
+use llmc::codes;
+use llmc::contract::Severity;
+use llmc::verifier::{
+    attach_excerpts, attach_positions, load_contract, load_output, redact_violations, run,
+    CompiledContract, ExcerptOptions, RunError, Verdict, VerdictStatus, VerifyOptions, Violation,
+};
+
+use batch::{load_outputs, OutputSource};
+use coverage::coverage;
+use diff::{diff_documents, VerdictDiff};
+use explain::explain;
+use generate::generate;
+use lsp::publish_diagnostics;
+use meta::{ContractMeta, RunMeta};
+use metrics::{metrics, Metrics, Summary};
+use mutate::mutants;
+use profile::profile;
+use remediate::render_remediation;
+use report::{junit, sarif, ReportedVerdict};
 
 const EXIT_PASS: i32 = 0;
 const EXIT_CONTRACT_FAILED: i32 = 1;
 const EXIT_INVALID_CONTRACT: i32 = 2;
 const EXIT_RUNTIME_IO: i32 = 3;
+/// Row mode: the output lies between the row fail and pass thresholds.
+const EXIT_PARTIAL: i32 = 4;
 
 #[derive(Debug, Parser)]
 #[command(name = "llmc")]
 #[command(about = "Verify LLM outputs against a JSON contract")]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
-    #[arg(short, long)]
-    contract: PathBuf,
-    #[arg(short, long)]
-    output: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[arg(short, long, required = true)]
+    contract: Option<PathBuf>,
+    /// Contract of a contract pack to verify against.
+    #[arg(long, value_name = "NAME")]
+    contract_name: Option<String>,
+    /// Output file to verify; repeat to verify several outputs in one batch.
+    #[arg(short, long, required_unless_present_any = ["outputs", "output_dir"])]
+    output: Vec<PathBuf>,
+    /// JSONL file holding one output per line, verified as a batch; repeatable.
+    #[arg(long, value_name = "FILE")]
+    outputs: Vec<PathBuf>,
+    /// Directory whose `.json` and `.jsonl` files are verified as a batch;
+    /// repeatable.
+    #[arg(long, value_name = "DIR")]
+    output_dir: Vec<PathBuf>,
+    /// Pass when at least this fraction of rows pass, overriding the contract's
+    /// `row_pass_threshold`.
+    #[arg(long, value_name = "FRACTION")]
+    row_pass_threshold: Option<f64>,
+    /// Fail below this fraction of passing rows and report `partial` (exit 4)
+    /// between it and the pass threshold, overriding the contract's
+    /// `row_fail_threshold`.
+    #[arg(long, value_name = "FRACTION")]
+    row_fail_threshold: Option<f64>,
+    /// List every rule in a `checks` array, including the ones that passed.
+    #[arg(long)]
+    include_passed: bool,
+    /// Lowest violation severity that makes a failing (or partial) verdict
+    /// exit non-zero.
+    #[arg(long, value_enum, default_value_t = FailOn::Info)]
+    fail_on: FailOn,
+    /// Extract the JSON object or array from surrounding text (code fences,
+    /// prose), as the contract's `output_format: "text_with_json"` does.
+    #[arg(long)]
+    extract: bool,
+    /// Attach an excerpt of the offending row to each violation.
+    #[arg(long)]
+    excerpt: bool,
+    /// Maximum excerpt length in characters.
+    #[arg(
+        long,
+        value_name = "CHARS",
+        default_value_t = 200,
+        requires = "excerpt"
+    )]
+    excerpt_max_len: usize,
+    /// Field whose values are redacted in excerpts and in the `actual`,
+    /// `expected` and message of violations about it; repeatable.
+    #[arg(long, value_name = "FIELD")]
+    redact: Vec<String>,
+    /// Show a minimal value satisfying the rule next to each violation.
+    #[arg(long)]
+    examples: bool,
+    /// Language of violation messages, e.g. `de`, overriding the contract's `locale`.
+    /// Hints and suggestions stay English.
+    #[arg(long)]
+    locale: Option<String>,
+    /// Report absent fields of `allowed_values`/`regex` rules and fields no
+    /// rule mentions, and reject duplicate rules.
+    #[arg(long)]
+    strict: bool,
+    /// Merge violations that differ only in their row into one entry with
+    /// `rows` and `count`.
+    #[arg(long, conflicts_with = "group_by")]
+    dedupe: bool,
+    /// Write run metrics (pass rate, totals, per-rule counts) as JSON to this file.
+    #[arg(long, value_name = "FILE")]
+    metrics: Option<PathBuf>,
+    /// Write a shields.io endpoint badge with the pass rate to this file.
+    #[arg(long, value_name = "FILE")]
+    badge: Option<PathBuf>,
+    /// Alternative verdict layout.
+    #[arg(long, value_enum)]
+    group_by: Option<GroupBy>,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = Format::Json)]
+    format: Format,
+    /// Write the verdict (or report) to this file instead of stdout.
+    #[arg(long, value_name = "FILE")]
+    out: Option<PathBuf>,
+    /// Run context recorded verbatim in the verdict's `meta.context` and the
+    /// metrics, e.g. `model=gpt-4o`; repeatable.
+    #[arg(long = "meta", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    context: Vec<(String, String)>,
+}
+
+fn parse_key_value(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{arg}'")),
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Compare two saved verdicts and report violations that appeared,
+    /// disappeared or changed.
+    VerdictDiff {
+        /// The earlier verdict, e.g. of the current model.
+        before: PathBuf,
+        /// The later verdict, e.g. of the candidate model.
+        after: PathBuf,
+    },
+    /// Explain a saved verdict in plain language, grouped by root cause.
+    ExplainFailure {
+        /// The verdict (or batch document) to explain.
+        verdict: PathBuf,
+    },
+    /// Render a remediation prompt from a saved verdict with a minijinja
+    /// template.
+    Remediate {
+        /// Template file; see the README for the available variables.
+        #[arg(long)]
+        template: PathBuf,
+        /// The verdict (or batch document) to render.
+        verdict: PathBuf,
+    },
+    /// Report rules that never fired and fields no rule reads over a corpus of
+    /// outputs.
+    Coverage {
+        #[arg(short, long)]
+        contract: PathBuf,
+        /// Contract of a contract pack to use.
+        #[arg(long, value_name = "NAME")]
+        contract_name: Option<String>,
+        /// Glob matching the output files, e.g. `outputs/**/*.json`.
+        #[arg(long, value_name = "GLOB")]
+        outputs: String,
+    },
+    /// Generate systematically broken variants of a passing output, one per
+    /// rule a single edit can break, for testing downstream failure handling.
+    Mutate {
+        #[arg(short, long)]
+        contract: PathBuf,
+        /// Contract of a contract pack to use.
+        #[arg(long, value_name = "NAME")]
+        contract_name: Option<String>,
+        /// An output that passes the contract.
+        #[arg(long, value_name = "FILE")]
+        from: PathBuf,
+        /// Write each variant to `<name>.json` in this directory instead of
+        /// printing it.
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<PathBuf>,
+    },
+    /// Generate random outputs that satisfy a contract, for load-testing
+    /// consumers and smoke-testing the contract itself.
+    Generate {
+        #[arg(short, long)]
+        contract: PathBuf,
+        /// Contract of a contract pack to use.
+        #[arg(long, value_name = "NAME")]
+        contract_name: Option<String>,
+        /// Number of outputs to generate.
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        /// Seed for reproducible outputs.
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Write each output to `<n>.json` in this directory instead of
+        /// printing it.
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<PathBuf>,
+    },
+    /// Browse the violations of a saved verdict (or batch document) in a
+    /// terminal UI.
+    #[cfg(feature = "tui")]
+    Tui {
+        /// The verdict (or batch document) to browse.
+        verdict: PathBuf,
+    },
+    /// Summarize the fields of one or more outputs without a contract: types,
+    /// null and missing rates, distinct values and lengths.
+    Profile {
+        /// Output files to profile together.
+        #[arg(required = true)]
+        outputs: Vec<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// The verdict document.
+    Json,
+    /// One LSP `PublishDiagnosticsParams` per output file, in a JSON array.
+    LspDiagnostics,
+    /// A SARIF 2.1.0 log, for code scanning annotations.
+    Sarif,
+    /// A JUnit XML report with one test case per output.
+    Junit,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum GroupBy {
+    /// Nest row violations under their row, with a per-row status.
+    Row,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum FailOn {
+    Error,
+    Warning,
+    Info,
+}
+
+impl From<FailOn> for Severity {
+    fn from(fail_on: FailOn) -> Self {
+        match fail_on {
+            FailOn::Error => Severity::Error,
+            FailOn::Warning => Severity::Warning,
+            FailOn::Info => Severity::Info,
+        }
+    }
 }
 
 fn main() {
     let cli = Cli::parse();
+    if let Some(command) = cli.command {
+        std::process::exit(run_command(command));
+    }
+    let contract_path = cli.contract.expect("clap requires --contract");
+    let fail_on = Severity::from(cli.fail_on);
+    let options = VerifyOptions {
+        row_pass_threshold: cli.row_pass_threshold,
+        row_fail_threshold: cli.row_fail_threshold,
+        excerpts: cli.excerpt.then_some(ExcerptOptions {
+            max_len: cli.excerpt_max_len,
+        }),
+        redact: cli.redact.clone(),
+        locale: cli.locale.clone(),
+        strict: cli.strict,
+        contract_name: cli.contract_name.clone(),
+        extract: cli.extract,
+        // Only the JSON verdict has room for merged entries.
+        dedupe: cli.dedupe && matches!(cli.format, Format::Json),
+    };
 
-    let (verdict, mut exit_code) = match run(&cli.contract, &cli.output) {
-        Ok(verdict) => {
-            let exit_code = if matches!(verdict.status, VerdictStatus::Pass) {
-                EXIT_PASS
+    let context: BTreeMap<String, String> = cli.context.iter().cloned().collect();
+
+    let started_at = Utc::now();
+    let timer = Instant::now();
+    // `--outputs` and `--output-dir` always make a batch, even of one output.
+    let batch = cli.output.len() > 1 || !cli.outputs.is_empty() || !cli.output_dir.is_empty();
+    let (verdicts, mut exit_code) = if batch {
+        let inputs = [&cli.output[..], &cli.outputs, &cli.output_dir];
+        run_batch(&contract_path, inputs, &options, fail_on)
+    } else {
+        let result = run(&contract_path, &cli.output[0], &options);
+        let (verdict, exit_code) = verdict_and_exit_code(result, fail_on);
+        (vec![(None, verdict)], exit_code)
+    };
+    let duration = timer.elapsed();
+    // An invalid contract says nothing about the outputs' health.
+    let mut run_metrics: Option<Metrics> = None;
+    if exit_code != EXIT_INVALID_CONTRACT {
+        let mut metrics = metrics(verdicts.iter().map(|(_, verdict)| verdict));
+        metrics.context = context.clone();
+        let run_metrics = run_metrics.insert(metrics);
+        let artifacts = [
+            (&cli.metrics, serde_json::to_value(&run_metrics)),
+            (&cli.badge, serde_json::to_value(run_metrics.badge())),
+        ];
+        for (path, artifact) in artifacts {
+            let Some(path) = path else { continue };
+            let artifact = artifact.expect("serialize metrics");
+            let written = serde_json::to_string_pretty(&artifact)
+                .map_err(io::Error::from)
+                .and_then(|json| fs::write(path, json));
+            if let Err(err) = written {
+                eprintln!("Failed to write {}: {err}", path.display());
+                exit_code = worst_exit_code(exit_code, EXIT_RUNTIME_IO);
+            }
+        }
+    }
+    let contract_meta = ContractMeta::read(&contract_path, cli.contract_name.as_deref());
+    let meta_output = (!batch).then(|| cli.output[0].as_path());
+    let first_path = cli
+        .output
+        .iter()
+        .chain(&cli.outputs)
+        .chain(&cli.output_dir)
+        .next()
+        .expect("clap requires an output");
+    let mut meta = RunMeta::new(contract_meta, meta_output, started_at, duration);
+    meta.context = context;
+
+    let rendered = match cli.format {
+        Format::Json => {
+            let public_verdicts: Vec<Value> = verdicts
+                .into_iter()
+                .map(|(source, mut verdict)| {
+                    if !cli.examples {
+                        for violation in &mut verdict.violations {
+                            violation.example = None;
+                        }
+                    }
+                    let mut public_verdict = match cli.group_by {
+                        Some(GroupBy::Row) => to_public_verdict_by_row(&verdict),
+                        None => to_public_verdict(&verdict),
+                    };
+                    if cli.include_passed {
+                        public_verdict["checks"] =
+                            serde_json::to_value(&verdict.checks).expect("serialize checks");
+                    }
+                    if let Some(source) = source {
+                        public_verdict["output"] = Value::String(source.label());
+                    }
+                    public_verdict
+                })
+                .collect();
+
+            // A batch prints one document wrapping every verdict, so it parses
+            // as a single JSON value.
+            let mut document = if batch {
+                let summary = run_metrics.as_ref().map(Metrics::summary);
+                to_batch_document(public_verdicts, summary)
             } else {
+                public_verdicts
+                    .into_iter()
+                    .next()
+                    .expect("one verdict per run")
+            };
+            document["meta"] = serde_json::to_value(meta).expect("serialize meta");
+            serialize_verdict(&document, &mut exit_code)
+        }
+        Format::LspDiagnostics => {
+            // One entry per file, in order of first appearance.
+            let mut files: Vec<(&Path, Vec<&Verdict>)> = Vec::new();
+            let mut index: HashMap<&Path, usize> = HashMap::new();
+            for reported in reported_verdicts(first_path, &verdicts) {
+                let idx = *index.entry(reported.path).or_insert_with(|| {
+                    files.push((reported.path, Vec::new()));
+                    files.len() - 1
+                });
+                files[idx].1.push(reported.verdict);
+            }
+            let params: Vec<Value> = files
+                .iter()
+                .map(|(path, file_verdicts)| publish_diagnostics(path, file_verdicts))
+                .collect();
+            serialize_verdict(&json!(params), &mut exit_code)
+        }
+        Format::Sarif => {
+            let log = sarif(&meta, &reported_verdicts(first_path, &verdicts));
+            serialize_verdict(&log, &mut exit_code)
+        }
+        Format::Junit => junit(&meta, &reported_verdicts(first_path, &verdicts)),
+    };
+    match &cli.out {
+        Some(path) => {
+            if let Err(err) = fs::write(path, format!("{rendered}\n")) {
+                eprintln!("Failed to write {}: {err}", path.display());
+                exit_code = worst_exit_code(exit_code, EXIT_RUNTIME_IO);
+            }
+        }
+        None => println!("{rendered}"),
+    }
+    std::process::exit(exit_code);
+}
+
+// Verdicts with the file each is about, for the formats that locate
+// violations in files. A verdict without a source (a single output, or an
+// invalid contract) is about the first output given.
+fn reported_verdicts<'a>(
+    first_path: &'a Path,
+    verdicts: &'a [(Option<OutputSource>, Verdict)],
+) -> Vec<ReportedVerdict<'a>> {
+    verdicts
+        .iter()
+        .map(|(source, verdict)| match source {
+            Some(source) => ReportedVerdict {
+                path: &source.path,
+                label: source.label(),
+                verdict,
+            },
+            None => ReportedVerdict {
+                path: first_path,
+                label: first_path.display().to_string(),
+                verdict,
+            },
+        })
+        .collect()
+}
+
+fn run_command(command: Command) -> i32 {
+    match command {
+        Command::VerdictDiff { before, after } => {
+            let loaded = load_json(&before).and_then(|before| {
+                let after = load_json(&after)?;
+                Ok((before, after))
+            });
+            let (before, after) = match loaded {
+                Ok(loaded) => loaded,
+                Err(err) => return print_run_error(err),
+            };
+            let diffs = diff_documents(&before, &after);
+            let mut exit_code = if diffs.iter().any(VerdictDiff::regressed) {
                 EXIT_CONTRACT_FAILED
+            } else {
+                EXIT_PASS
+            };
+            let document = match diffs.as_slice() {
+                [diff] => serde_json::to_value(diff),
+                diffs => serde_json::to_value(diffs).map(|diffs| json!({ "verdicts": diffs })),
+            }
+            .expect("serialize verdict diff");
+            println!("{}", serialize_verdict(&document, &mut exit_code));
+            exit_code
+        }
+        Command::ExplainFailure { verdict } => match load_json(&verdict) {
+            Ok(verdict) => {
+                println!("{}", explain(&verdict));
+                EXIT_PASS
+            }
+            Err(err) => print_run_error(err),
+        },
+        Command::Remediate { template, verdict } => {
+            let loaded = fs::read_to_string(&template)
+                .map_err(RunError::Io)
+                .and_then(|template| Ok((template, load_json(&verdict)?)));
+            let (template, verdict) = match loaded {
+                Ok(loaded) => loaded,
+                Err(err) => return print_run_error(err),
+            };
+            match render_remediation(&template, &verdict) {
+                Ok(prompt) => {
+                    print!("{prompt}");
+                    EXIT_PASS
+                }
+                Err(err) => print_failure(
+                    &failure_verdict("Runtime", format!("Template error: {err:#}")),
+                    EXIT_RUNTIME_IO,
+                ),
+            }
+        }
+        Command::Coverage {
+            contract,
+            contract_name,
+            outputs,
+        } => {
+            let paths = match expand_glob(&outputs) {
+                Ok(paths) => paths,
+                Err(detail) => {
+                    return print_failure(&failure_verdict("Runtime", detail), EXIT_RUNTIME_IO)
+                }
+            };
+            let loaded = load_contract(&contract, &named_contract(contract_name))
+                .and_then(CompiledContract::new)
+                .and_then(|contract| {
+                    let outputs = paths.iter().map(|path| load_json(path)).collect::<Result<
+                        Vec<Value>,
+                        RunError,
+                    >>(
+                    )?;
+                    Ok((contract, outputs))
+                });
+            let (contract, outputs) = match loaded {
+                Ok(loaded) => loaded,
+                Err(err) => return print_run_error(err),
+            };
+            let labels: Vec<String> = paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            let batch: Vec<(&str, &Value)> =
+                labels.iter().map(String::as_str).zip(&outputs).collect();
+            let verdicts = contract.verify_batch(&batch);
+            let document = serde_json::to_value(coverage(contract.contract(), &outputs, &verdicts))
+                .expect("serialize coverage");
+            let mut exit_code = EXIT_PASS;
+            println!("{}", serialize_verdict(&document, &mut exit_code));
+            exit_code
+        }
+        Command::Mutate {
+            contract,
+            contract_name,
+            from,
+            out_dir,
+        } => {
+            let loaded = load_contract(&contract, &named_contract(contract_name))
+                .and_then(CompiledContract::new)
+                .and_then(|contract| Ok((contract, load_json(&from)?)));
+            let (contract, output) = match loaded {
+                Ok(loaded) => loaded,
+                Err(err) => return print_run_error(err),
+            };
+            // Mutants of a failing output would not isolate one failure each.
+            let verdict = contract.verify(&output);
+            if verdict.status == VerdictStatus::Fail {
+                return print_failure(&verdict, EXIT_CONTRACT_FAILED);
+            }
+            let mut documents = Vec::new();
+            for mutant in mutants(&contract, &output) {
+                let mut document = serde_json::to_value(&mutant).expect("serialize mutant");
+                if let Some(out_dir) = &out_dir {
+                    let path = out_dir.join(format!("{}.json", mutant.name));
+                    let contents =
+                        serde_json::to_string_pretty(&mutant.output).expect("serialize mutant");
+                    let written = fs::create_dir_all(out_dir)
+                        .and_then(|()| fs::write(&path, contents + "\n"));
+                    if let Err(err) = written {
+                        return print_run_error(RunError::Io(err));
+                    }
+                    document["output"] = json!(path.display().to_string());
+                }
+                documents.push(document);
+            }
+            let mut exit_code = EXIT_PASS;
+            println!(
+                "{}",
+                serialize_verdict(&json!({ "mutants": documents }), &mut exit_code)
+            );
+            exit_code
+        }
+        Command::Generate {
+            contract,
+            contract_name,
+            count,
+            seed,
+            out_dir,
+        } => {
+            let contract = match load_contract(&contract, &named_contract(contract_name))
+                .and_then(CompiledContract::new)
+            {
+                Ok(contract) => contract,
+                Err(err) => return print_run_error(err),
+            };
+            let mut rng = match seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            // Every output is verified: one that fails points at rules the
+            // generator cannot satisfy or that contradict each other.
+            let mut exit_code = EXIT_PASS;
+            let mut documents = Vec::new();
+            for idx in 1..=count {
+                let output = generate(contract.contract(), &mut rng);
+                let verdict = contract.verify(&output);
+                if verdict.status == VerdictStatus::Fail {
+                    exit_code = EXIT_CONTRACT_FAILED;
+                }
+                let mut document = to_public_verdict(&verdict);
+                document["output"] = match &out_dir {
+                    Some(out_dir) => {
+                        let path = out_dir.join(format!("{idx}.json"));
+                        let contents =
+                            serde_json::to_string_pretty(&output).expect("serialize output");
+                        let written = fs::create_dir_all(out_dir)
+                            .and_then(|()| fs::write(&path, contents + "\n"));
+                        if let Err(err) = written {
+                            return print_run_error(RunError::Io(err));
+                        }
+                        json!(path.display().to_string())
+                    }
+                    None => output,
+                };
+                documents.push(document);
+            }
+            println!(
+                "{}",
+                serialize_verdict(&json!({ "outputs": documents }), &mut exit_code)
+            );
+            exit_code
+        }
+        #[cfg(feature = "tui")]
+        Command::Tui { verdict: path } => {
+            let verdict = match load_json(&path) {
+                Ok(verdict) => verdict,
+                Err(err) => return print_run_error(err),
+            };
+            if !tui::has_verdicts(&verdict) {
+                return print_failure(
+                    &failure_verdict(
+                        "Runtime",
+                        format!("{} holds no verdicts to browse.", path.display()),
+                    ),
+                    EXIT_RUNTIME_IO,
+                );
+            }
+            if !io::IsTerminal::is_terminal(&io::stdout()) {
+                return print_failure(
+                    &failure_verdict("Runtime", "llmc tui needs a terminal.".to_string()),
+                    EXIT_RUNTIME_IO,
+                );
+            }
+            match tui::browse(&verdict, &path.display().to_string()) {
+                Ok(()) => EXIT_PASS,
+                Err(err) => print_run_error(RunError::Io(err)),
+            }
+        }
+        Command::Profile { outputs } => {
+            let loaded: Result<Vec<Value>, RunError> =
+                outputs.iter().map(|path| load_json(path)).collect();
+            let outputs = match loaded {
+                Ok(outputs) => outputs,
+                Err(err) => return print_run_error(err),
+            };
+            let document = serde_json::to_value(profile(&outputs)).expect("serialize profile");
+            let mut exit_code = EXIT_PASS;
+            println!("{}", serialize_verdict(&document, &mut exit_code));
+            exit_code
+        }
+    }
+}
+
+fn named_contract(contract_name: Option<String>) -> VerifyOptions {
+    VerifyOptions {
+        contract_name,
+        ..VerifyOptions::default()
+    }
+}
+
+// Reads a JSON file (a saved verdict or an output) with the error handling
+// of outputs.
+fn load_json(path: &Path) -> Result<Value, RunError> {
+    load_output(path).map(|(_, verdict)| verdict)
+}
+
+// Matching files in sorted order; a bad pattern or no match is an error.
+fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>, String> {
+    let mut paths = glob::glob(pattern)
+        .map_err(|err| format!("Invalid glob pattern '{pattern}': {err}"))?
+        .collect::<Result<Vec<PathBuf>, _>>()
+        .map_err(|err| format!("I/O error: {err}"))?;
+    if paths.is_empty() {
+        return Err(format!("No output files match '{pattern}'."));
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn print_run_error(err: RunError) -> i32 {
+    let (verdict, exit_code) = verdict_and_exit_code(Err(err), Severity::Info);
+    print_failure(&verdict, exit_code)
+}
+
+fn print_failure(verdict: &Verdict, mut exit_code: i32) -> i32 {
+    println!(
+        "{}",
+        serialize_verdict(&to_public_verdict(verdict), &mut exit_code)
+    );
+    exit_code
+}
+
+// Batch mode verifies each output. Outputs that cannot be read get a
+// runtime verdict of their own; an invalid contract aborts the whole batch.
+// `inputs` holds the `--output` files, `--outputs` JSONL files and
+// `--output-dir` directories.
+fn run_batch(
+    contract_path: &Path,
+    [files, jsonl, dirs]: [&[PathBuf]; 3],
+    options: &VerifyOptions,
+    fail_on: Severity,
+) -> (Vec<(Option<OutputSource>, Verdict)>, i32) {
+    let contract = match load_contract(contract_path, options)
+        .and_then(CompiledContract::new)
+        .map(|contract| contract.with_dedupe(options.dedupe))
+    {
+        Ok(contract) => contract,
+        Err(err) => {
+            let (verdict, exit_code) = verdict_and_exit_code(Err(err), fail_on);
+            return (vec![(None, verdict)], exit_code);
+        }
+    };
+    let loaded = load_outputs(contract.contract(), files, jsonl, dirs);
+
+    let labels: Vec<String> = loaded.iter().map(|(source, _)| source.label()).collect();
+    let batch: Vec<(&str, &Value)> = loaded
+        .iter()
+        .zip(&labels)
+        .filter_map(|((_, output), label)| {
+            let (_, output, _) = output.as_ref().ok()?;
+            Some((label.as_str(), output))
+        })
+        .collect();
+    let mut verified = contract.verify_batch(&batch).into_iter();
+
+    let mut exit_code = EXIT_PASS;
+    let verdicts = loaded
+        .into_iter()
+        .map(|(source, output)| {
+            let result = output.map(|(text, output, extraction)| {
+                let mut verdict = verified.next().expect("one verdict per loaded output");
+                verdict.extraction = extraction;
+                attach_positions(&mut verdict, &text);
+                source.shift_positions(&mut verdict);
+                if let Some(excerpts) = &options.excerpts {
+                    attach_excerpts(&mut verdict, &output, excerpts, &options.redact);
+                }
+                redact_violations(&mut verdict, &options.redact);
+                verdict
+            });
+            let (verdict, code) = verdict_and_exit_code(result, fail_on);
+            exit_code = worst_exit_code(exit_code, code);
+            (Some(source), verdict)
+        })
+        .collect();
+    (verdicts, exit_code)
+}
+
+fn verdict_and_exit_code(result: Result<Verdict, RunError>, fail_on: Severity) -> (Verdict, i32) {
+    match result {
+        Ok(verdict) => {
+            let exit_code = if verdict.fails_at(fail_on) {
+                EXIT_CONTRACT_FAILED
+            } else if verdict.partial_at(fail_on) {
+                EXIT_PARTIAL
+            } else {
+                EXIT_PASS
             };
             (verdict, exit_code)
         }
@@ -44,6 +779,14 @@ fn main() {
             failure_verdict("InvalidContract", err.to_string()),
             EXIT_INVALID_CONTRACT,
         ),
+        Err(RunError::InvalidContractRule(detail)) => (
+            failure_verdict("InvalidContract", detail),
+            EXIT_INVALID_CONTRACT,
+        ),
+        Err(RunError::ContractNotFound(detail)) => (
+            failure_verdict("InvalidContract", format!("Contract not found: {detail}")),
+            EXIT_INVALID_CONTRACT,
+        ),
         Err(RunError::InvalidOutput(err)) => (
             failure_verdict("Runtime", format!("Invalid output JSON: {err}")),
             EXIT_RUNTIME_IO,
@@ -52,18 +795,38 @@ fn main() {
             failure_verdict("Runtime", format!("I/O error: {err}")),
             EXIT_RUNTIME_IO,
         ),
-    };
+    }
+}
+
+// Exit codes are not ordered by severity: a partial output (4) is better
+// than a failing one (1).
+fn worst_exit_code(a: i32, b: i32) -> i32 {
+    const BY_SEVERITY: [i32; 5] = [
+        EXIT_PASS,
+        EXIT_PARTIAL,
+        EXIT_CONTRACT_FAILED,
+        EXIT_INVALID_CONTRACT,
+        EXIT_RUNTIME_IO,
+    ];
+    let rank = |code: i32| BY_SEVERITY.iter().position(|&known| known == code);
+    if rank(a) >= rank(b) {
+        a
+    } else {
+        b
+    }
+}
 
-    let public_verdict = to_public_verdict(&verdict);
-    let serialized = match serde_json::to_string_pretty(&public_verdict) {
+fn serialize_verdict(public_verdict: &Value, exit_code: &mut i32) -> String {
+    match serde_json::to_string_pretty(public_verdict) {
         Ok(serialized) => serialized,
         Err(err) => {
-            exit_code = EXIT_RUNTIME_IO;
+            *exit_code = EXIT_RUNTIME_IO;
             serde_json::to_string_pretty(&json!({
                 "status": "fail",
                 "violations": [
                     {
                         "rule": "runtime",
+                        "code": codes::RUNTIME_ERROR,
                         "field": "",
                         "message": format!("Failed to serialize verdict: {err}")
                     }
@@ -71,23 +834,90 @@ fn main() {
             }))
             .expect("failed to serialize fallback verdict")
         }
-    };
+    }
+}
 
-    println!("{serialized}");
-    std::process::exit(exit_code);
+// The batch status is that of its worst verdict.
+fn to_batch_document(verdicts: Vec<Value>, summary: Option<Summary>) -> Value {
+    let status = ["fail", "partial", "partial_pass"]
+        .into_iter()
+        .find(|status| verdicts.iter().any(|verdict| verdict["status"] == *status))
+        .unwrap_or("pass");
+    let mut document = json!({ "status": status });
+    if let Some(summary) = summary {
+        document["summary"] = serde_json::to_value(summary).expect("serialize summary");
+    }
+    document["verdicts"] = Value::Array(verdicts);
+    document
 }
 
 fn to_public_verdict(verdict: &Verdict) -> Value {
-    let status = if matches!(verdict.status, VerdictStatus::Pass) {
-        "pass"
-    } else {
-        "fail"
+    let status = match verdict.status {
+        VerdictStatus::Pass => "pass",
+        VerdictStatus::PartialPass => "partial_pass",
+        VerdictStatus::Partial => "partial",
+        VerdictStatus::Fail => "fail",
     };
     let violations: Vec<Value> = verdict.violations.iter().map(to_public_violation).collect();
-    json!({
+    let mut public = json!({
         "status": status,
         "violations": violations
-    })
+    });
+    if let Some(score) = verdict.score {
+        public["score"] = Value::from(score);
+    }
+    if let Some(pass_threshold) = verdict.pass_threshold {
+        public["pass_threshold"] = Value::from(pass_threshold);
+    }
+    if !verdict.groups.is_empty() {
+        public["groups"] = serde_json::to_value(&verdict.groups).expect("serialize groups");
+    }
+    if let Some(row_pass_rate) = verdict.row_pass_rate {
+        public["row_pass_rate"] = Value::from(row_pass_rate);
+    }
+    if let Some(row_pass_threshold) = verdict.row_pass_threshold {
+        public["row_pass_threshold"] = Value::from(row_pass_threshold);
+        if let Some(row_fail_threshold) = verdict.row_fail_threshold {
+            public["row_fail_threshold"] = Value::from(row_fail_threshold);
+        }
+        public["rows"] = serde_json::to_value(&verdict.rows).expect("serialize rows");
+    }
+    if let Some(extraction) = &verdict.extraction {
+        public["extraction"] = serde_json::to_value(extraction).expect("serialize extraction");
+    }
+    public
+}
+
+// Violations tied to a row move under `rows[].violations`; the top-level
+// `violations` keeps the ones about the output as a whole.
+fn to_public_verdict_by_row(verdict: &Verdict) -> Value {
+    let mut public = to_public_verdict(verdict);
+    let output_violations: Vec<Value> = verdict
+        .violations
+        .iter()
+        .filter(|violation| violation.row.is_none())
+        .map(to_public_violation)
+        .collect();
+    let rows: Vec<Value> = verdict
+        .rows
+        .iter()
+        .map(|summary| {
+            let violations: Vec<Value> = verdict
+                .violations
+                .iter()
+                .filter(|violation| violation.row == Some(summary.row))
+                .map(to_public_violation)
+                .collect();
+            json!({
+                "row": summary.row,
+                "status": summary.status,
+                "violations": violations
+            })
+        })
+        .collect();
+    public["violations"] = Value::Array(output_violations);
+    public["rows"] = Value::Array(rows);
+    public
 }
 
 fn to_public_violation(violation: &Violation) -> Value {
@@ -105,26 +935,70 @@ fn to_public_violation(violation: &Violation) -> Value {
         "field",
         Value::String(violation.field.clone().unwrap_or_default()),
     );
+    obj.insert("code", Value::String(violation.code.to_string()));
     obj.insert("message", Value::String(violation.detail.clone()));
+    obj.insert(
+        "severity",
+        serde_json::to_value(violation.severity).expect("serialize severity"),
+    );
     if let Some(expected) = &violation.expected {
         obj.insert("expected", expected.clone());
     }
     if let Some(actual) = &violation.actual {
         obj.insert("actual", actual.clone());
     }
+    if let Some(example) = &violation.example {
+        obj.insert("example", example.clone());
+    }
+    if let Some(row) = violation.row {
+        obj.insert("row", Value::from(row));
+    }
+    if let Some(excerpt) = &violation.excerpt {
+        obj.insert("excerpt", Value::String(excerpt.clone()));
+    }
+    if let Some(position) = &violation.position {
+        obj.insert(
+            "position",
+            serde_json::to_value(position).expect("serialize position"),
+        );
+    }
+    if let Some(end_position) = &violation.end_position {
+        obj.insert(
+            "end_position",
+            serde_json::to_value(end_position).expect("serialize position"),
+        );
+    }
+    if let Some(hint) = &violation.hint {
+        obj.insert("hint", Value::String(hint.clone()));
+    }
+    if let Some(suggestion) = &violation.suggestion {
+        obj.insert("suggestion", Value::String(suggestion.clone()));
+    }
+    if let Some(group) = &violation.group {
+        obj.insert("group", Value::String(group.clone()));
+    }
+    if !violation.rows.is_empty() {
+        obj.insert("rows", json!(violation.rows));
+        obj.insert("count", Value::from(violation.rows.len()));
+    }
+    if !violation.enforced {
+        obj.insert("enforced", Value::Bool(false));
+    }
     serde_json::to_value(obj).expect("serialize public violation")
 }
 
 fn failure_verdict(rule_name: &str, detail: String) -> Verdict {
     Verdict {
         status: VerdictStatus::Fail,
-        violations: vec![Violation {
-            rule_name: rule_name.to_string(),
-            detail,
-            field: None,
-            rule: None,
-            expected: None,
-            actual: None,
-        }],
+        violations: vec![Violation::new(rule_name, detail)],
+        score: None,
+        pass_threshold: None,
+        groups: Vec::new(),
+        checks: Vec::new(),
+        rows: Vec::new(),
+        row_pass_rate: None,
+        row_pass_threshold: None,
+        row_fail_threshold: None,
+        extraction: None,
     }
 }
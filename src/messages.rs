@@ -0,0 +1,138 @@
+//! Localized violation messages. Catalogs map violation codes to message
+//! templates; the built-in ones are embedded in the binary and contracts can
+//! override or add templates per locale under `messages`. Only messages are
+//! localized; hints and suggestions stay English.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::contract::Contract;
+use crate::verifier::Violation;
+
+/// Messages are written in English by the rules themselves.
+const DEFAULT_LOCALE: &str = "en";
+
+const CATALOGS: [(&str, &str); 1] = [("de", include_str!("../locales/de.json"))];
+
+// Template keys for the `{row_prefix}` placeholder, rendered only for
+// violations raised for a row or merged from several rows.
+const ROW_PREFIX: &str = "row_prefix";
+const DEFAULT_ROW_PREFIX: &str = "Row {row}: ";
+const ROWS_PREFIX: &str = "rows_prefix";
+const DEFAULT_ROWS_PREFIX: &str = "In {count} rows: ";
+
+/// Message templates for the contract's locale.
+pub struct Messages {
+    templates: BTreeMap<String, String>,
+}
+
+impl Messages {
+    /// The built-in catalog for the contract's `locale` merged with the
+    /// contract's own `messages` for it. `None` when there is nothing to
+    /// translate, e.g. English without overrides.
+    pub fn for_contract(contract: &Contract) -> Option<Self> {
+        let locale = contract.locale.as_deref().unwrap_or(DEFAULT_LOCALE);
+        let mut templates = builtin_catalog(locale).unwrap_or_default();
+        if let Some(overrides) = contract.messages.get(locale) {
+            templates.extend(overrides.clone());
+        }
+        (!templates.is_empty()).then_some(Messages { templates })
+    }
+
+    /// Replaces the violation's message with the template for its code, if
+    /// any. `field` stands in for violations that do not name their field.
+    pub fn localize(&self, violation: &mut Violation, field: Option<&str>) {
+        let Some(template) = self.templates.get(violation.code) else {
+            return;
+        };
+        let row_prefix = match (violation.row, violation.rows.len()) {
+            (Some(row), _) => self
+                .templates
+                .get(ROW_PREFIX)
+                .map_or(DEFAULT_ROW_PREFIX, String::as_str)
+                .replace("{row}", &row.to_string()),
+            (None, 0) => String::new(),
+            (None, count) => self
+                .templates
+                .get(ROWS_PREFIX)
+                .map_or(DEFAULT_ROWS_PREFIX, String::as_str)
+                .replace("{count}", &count.to_string()),
+        };
+        let placeholders = [
+            ("row_prefix", row_prefix),
+            (
+                "row",
+                violation.row.map(|row| row.to_string()).unwrap_or_default(),
+            ),
+            (
+                "field",
+                violation
+                    .field
+                    .as_deref()
+                    .or(field)
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+            (
+                "rule",
+                violation
+                    .rule
+                    .clone()
+                    .unwrap_or_else(|| violation.rule_name.clone()),
+            ),
+            ("expected", render(violation.expected.as_ref())),
+            ("actual", render(violation.actual.as_ref())),
+            ("code", violation.code.to_string()),
+            ("message", violation.detail.clone()),
+        ];
+        violation.detail = substitute(template, &placeholders);
+    }
+}
+
+// Expands `{name}` placeholders in one pass over the template, so braces in
+// the substituted values, which may come from the output, stay as they are.
+// Unknown placeholders are kept verbatim.
+fn substitute(template: &str, placeholders: &[(&str, String)]) -> String {
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        message.push_str(&rest[..start]);
+        let token = &rest[start..];
+        let value = token.find('}').and_then(|end| {
+            let (_, value) = placeholders
+                .iter()
+                .find(|(name, _)| *name == &token[1..end])?;
+            Some((value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                message.push_str(value);
+                rest = &token[end + 1..];
+            }
+            None => {
+                message.push('{');
+                rest = &token[1..];
+            }
+        }
+    }
+    message.push_str(rest);
+    message
+}
+
+/// Whether messages can be produced in `locale`: English, a built-in catalog
+/// or one the contract brings along.
+pub fn is_known_locale(contract: &Contract, locale: &str) -> bool {
+    locale == DEFAULT_LOCALE
+        || builtin_catalog(locale).is_some()
+        || contract.messages.contains_key(locale)
+}
+
+fn builtin_catalog(locale: &str) -> Option<BTreeMap<String, String>> {
+    let (_, catalog) = CATALOGS.iter().find(|(name, _)| *name == locale)?;
+    Some(serde_json::from_str(catalog).expect("embedded message catalog is valid JSON"))
+}
+
+fn render(value: Option<&Value>) -> String {
+    value.map(Value::to_string).unwrap_or_default()
+}
@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Provenance of a verdict, for audit trails: which tool checked which output
+/// against which contract, and when.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunMeta {
+    pub tool: &'static str,
+    pub tool_version: &'static str,
+    pub contract: ContractMeta,
+    /// The verified output file; absent for batch documents.
+    pub output: Option<String>,
+    pub started_at: String,
+    pub finished_at: String,
+    pub duration_ms: u128,
+    /// Run context given with `--meta key=value` (model, temperature, prompt
+    /// id, ...), recorded verbatim.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub context: BTreeMap<String, String>,
+}
+
+/// The contract file as it was at verification time. Name and version are
+/// read from the file even when the contract is invalid; the hash covers its
+/// exact bytes. For contract packs they are those of the selected contract,
+/// whose version defaults to the pack's.
+#[derive(Debug, Clone, Serialize)]
+pub struct ContractMeta {
+    pub path: String,
+    pub name: Option<String>,
+    pub version: Option<u64>,
+    pub sha256: Option<String>,
+}
+
+impl ContractMeta {
+    pub fn read(path: &Path, contract_name: Option<&str>) -> Self {
+        let bytes = fs::read(path).ok();
+        let parsed: Option<Value> = bytes
+            .as_deref()
+            .and_then(|bytes| serde_json::from_slice(bytes).ok());
+        let pack = parsed
+            .as_ref()
+            .filter(|parsed| parsed.get("contracts").is_some());
+        let contract = match (pack, contract_name) {
+            (Some(pack), Some(name)) => pack
+                .get("contracts")
+                .and_then(|contracts| contracts.get(name)),
+            (Some(_), None) => None,
+            (None, _) => parsed.as_ref(),
+        };
+        let field = |key: &str| contract.and_then(|contract| contract.get(key).cloned());
+        let name = field("contract")
+            .and_then(|name| name.as_str().map(str::to_string))
+            .or_else(|| contract.and(pack).and(contract_name).map(str::to_string));
+        let version = field("version").or_else(|| {
+            contract
+                .and(pack)
+                .and_then(|pack| pack.get("version").cloned())
+        });
+        ContractMeta {
+            path: path.display().to_string(),
+            name,
+            version: version.and_then(|version| version.as_u64()),
+            sha256: bytes.map(|bytes| format!("{:x}", Sha256::digest(bytes))),
+        }
+    }
+}
+
+impl RunMeta {
+    pub fn new(
+        contract: ContractMeta,
+        output_path: Option<&Path>,
+        started_at: DateTime<Utc>,
+        duration: Duration,
+    ) -> Self {
+        let finished_at = started_at + duration;
+        RunMeta {
+            tool: env!("CARGO_PKG_NAME"),
+            tool_version: env!("CARGO_PKG_VERSION"),
+            contract,
+            output: output_path.map(|path| path.display().to_string()),
+            started_at: started_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            finished_at: finished_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            duration_ms: duration.as_millis(),
+            context: BTreeMap::new(),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Compact run metrics and a shields.io endpoint badge, for dashboards that
+//! should not have to parse full verdicts. The metrics schema is versioned by
+//! `schema_version` and only ever gains fields within a version.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use llmc::verifier::{Verdict, VerdictStatus};
+
+const SCHEMA_VERSION: u32 = 1;
+const BADGE_LABEL: &str = "contract";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Metrics {
+    pub schema_version: u32,
+    pub outputs: usize,
+    pub passed: usize,
+    pub partial_passed: usize,
+    /// Outputs between the row fail and pass thresholds.
+    pub partial: usize,
+    pub failed: usize,
+    /// Fraction of outputs that passed, partial passes included.
+    pub pass_rate: f64,
+    pub violations: usize,
+    /// One entry per contract rule, in contract order.
+    pub rules: Vec<RuleMetrics>,
+    /// Run context given with `--meta`, for slicing by model configuration.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub context: BTreeMap<String, String>,
+    /// Violations no check accounts for, such as runtime errors of unreadable
+    /// outputs or strict mode's unknown fields, by rule name. Only the summary
+    /// lists them.
+    #[serde(skip)]
+    pub unchecked_violations: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleMetrics {
+    pub rule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub violations: usize,
+    /// Outputs with at least one violation of the rule.
+    pub failed_outputs: usize,
+}
+
+/// Counts for the `summary` of a batch document.
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    pub outputs: usize,
+    pub passed: usize,
+    pub partial_passed: usize,
+    pub partial: usize,
+    pub failed: usize,
+    pub violations: usize,
+    /// Violations per rule name, for the rules with any; violations outside
+    /// contract rules count under their kind, e.g. `Runtime`.
+    pub violations_by_rule: BTreeMap<String, usize>,
+}
+
+/// shields.io endpoint badge JSON.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Badge {
+    pub schema_version: u32,
+    pub label: &'static str,
+    pub message: String,
+    pub color: &'static str,
+}
+
+pub fn metrics<'a>(verdicts: impl IntoIterator<Item = &'a Verdict>) -> Metrics {
+    let mut metrics = Metrics {
+        schema_version: SCHEMA_VERSION,
+        outputs: 0,
+        passed: 0,
+        partial_passed: 0,
+        partial: 0,
+        failed: 0,
+        pass_rate: 0.0,
+        violations: 0,
+        rules: Vec::new(),
+        context: BTreeMap::new(),
+        unchecked_violations: BTreeMap::new(),
+    };
+    for verdict in verdicts {
+        metrics.outputs += 1;
+        match verdict.status {
+            VerdictStatus::Pass => metrics.passed += 1,
+            VerdictStatus::PartialPass => metrics.partial_passed += 1,
+            VerdictStatus::Partial => metrics.partial += 1,
+            VerdictStatus::Fail => metrics.failed += 1,
+        }
+        // A violation merged by `--dedupe` counts once per row.
+        metrics.violations += verdict
+            .violations
+            .iter()
+            .map(|violation| violation.rows.len().max(1))
+            .sum::<usize>();
+        for violation in verdict
+            .violations
+            .iter()
+            .filter(|violation| !has_check(verdict, &violation.rule_name))
+        {
+            *metrics
+                .unchecked_violations
+                .entry(violation.rule_name.clone())
+                .or_default() += violation.rows.len().max(1);
+        }
+        // Every verdict of a run lists the same checks in the same order;
+        // verdicts of unreadable outputs list none.
+        for (idx, check) in verdict.checks.iter().enumerate() {
+            if idx == metrics.rules.len() {
+                metrics.rules.push(RuleMetrics {
+                    rule: check.rule.clone(),
+                    field: check.field.clone(),
+                    violations: 0,
+                    failed_outputs: 0,
+                });
+            }
+            let rule = &mut metrics.rules[idx];
+            rule.violations += check.violations;
+            if !check.passed {
+                rule.failed_outputs += 1;
+            }
+        }
+    }
+    if metrics.outputs > 0 {
+        metrics.pass_rate =
+            (metrics.passed + metrics.partial_passed) as f64 / metrics.outputs as f64;
+    }
+    metrics
+}
+
+// Whether a contract rule's check of the verdict counts violations named
+// `rule_name`, e.g. `required_field` those named `RequiredField`.
+fn has_check(verdict: &Verdict, rule_name: &str) -> bool {
+    let mut rule = String::with_capacity(rule_name.len() + 4);
+    for (idx, c) in rule_name.char_indices() {
+        if c.is_uppercase() && idx > 0 {
+            rule.push('_');
+        }
+        rule.push(c.to_ascii_lowercase());
+    }
+    verdict.checks.iter().any(|check| check.rule == rule)
+}
+
+impl Metrics {
+    pub fn summary(&self) -> Summary {
+        let mut violations_by_rule = self.unchecked_violations.clone();
+        for rule in self.rules.iter().filter(|rule| rule.violations > 0) {
+            *violations_by_rule.entry(rule.rule.clone()).or_default() += rule.violations;
+        }
+        Summary {
+            outputs: self.outputs,
+            passed: self.passed,
+            partial_passed: self.partial_passed,
+            partial: self.partial,
+            failed: self.failed,
+            violations: self.violations,
+            violations_by_rule,
+        }
+    }
+
+    pub fn badge(&self) -> Badge {
+        let percent = self.pass_rate * 100.0;
+        let color = match percent {
+            p if p >= 100.0 => "brightgreen",
+            p if p >= 90.0 => "green",
+            p if p >= 75.0 => "yellow",
+            p if p >= 50.0 => "orange",
+            _ => "red",
+        };
+        Badge {
+            schema_version: 1,
+            label: BADGE_LABEL,
+            message: format!("{}% pass", percent.floor()),
+            color,
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//! Systematically broken variants of a passing output, one per contract rule
+//! that can be broken by a single edit, for testing that downstream
+//! pipelines react to contract failures.
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use llmc::codes;
+use llmc::contract::{Contract, Rule, ValueType};
+use llmc::verifier::CompiledContract;
+
+// A string no realistic allowed-value list or pattern includes.
+const MUTANT_STRING: &str = "__llmc_mutant__";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Mutant {
+    /// `<rule>-<field>`, unique within a run.
+    pub name: String,
+    pub rule: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// The violation code the mutant is meant to trigger.
+    pub code: &'static str,
+    /// Whether verifying the mutant raises `code`.
+    pub triggered: bool,
+    pub output: Value,
+}
+
+/// One mutant per rule of `contract` that a single edit of `output` can
+/// break. Row-level edits apply to the first row of array outputs;
+/// `unique_values` copies the first row's value into the second.
+pub fn mutants(compiled: &CompiledContract, output: &Value) -> Vec<Mutant> {
+    let contract = compiled.contract();
+    let mut mutants: Vec<Mutant> = Vec::new();
+    for (_, spec) in contract.rule_specs() {
+        let Some((code, output)) = mutate(contract, &spec.rule, output) else {
+            continue;
+        };
+        let field = spec.rule.field().map(str::to_string);
+        let base = match &field {
+            Some(field) => format!("{}-{field}", spec.rule.name()),
+            None => spec.rule.name().to_string(),
+        };
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while mutants.iter().any(|mutant| mutant.name == name) {
+            name = format!("{base}-{suffix}");
+            suffix += 1;
+        }
+        let triggered = compiled
+            .verify(&output)
+            .violations
+            .iter()
+            .any(|violation| violation.code == code);
+        mutants.push(Mutant {
+            name,
+            rule: spec.rule.name(),
+            field,
+            code,
+            triggered,
+            output,
+        });
+    }
+    mutants
+}
+
+fn mutate(contract: &Contract, rule: &Rule, output: &Value) -> Option<(&'static str, Value)> {
+    let mut mutant = output.clone();
+    let code = match rule {
+        Rule::RequiredField { field, .. } => {
+            first_row(&mut mutant)?.remove(field)?;
+            codes::MISSING_FIELD
+        }
+        Rule::FieldType { field, expected } => {
+            let wrong = match expected {
+                ValueType::String => json!(0),
+                _ => json!(MUTANT_STRING),
+            };
+            *first_row(&mut mutant)?.get_mut(field)? = wrong;
+            codes::TYPE_MISMATCH
+        }
+        Rule::AllowedValues { field, values, .. } => {
+            let wrong = json!(MUTANT_STRING);
+            if values.contains(&wrong) {
+                return None;
+            }
+            *first_row(&mut mutant)?.get_mut(field)? = wrong;
+            codes::DISALLOWED_VALUE
+        }
+        Rule::Const { field, value, .. } => {
+            let wrong = json!(MUTANT_STRING);
+            if *value == wrong {
+                return None;
+            }
+            *first_row(&mut mutant)?.get_mut(field)? = wrong;
+            codes::CONST_MISMATCH
+        }
+        Rule::Regex {
+            field,
+            pattern,
+            pattern_ref,
+        } => {
+            let pattern = contract.resolve_pattern(pattern.as_deref(), pattern_ref.as_deref())?;
+            let regex = Regex::new(pattern).ok()?;
+            let wrong = ["", "!", MUTANT_STRING]
+                .into_iter()
+                .find(|candidate| !regex.is_match(candidate))?;
+            *first_row(&mut mutant)?.get_mut(field)? = json!(wrong);
+            codes::PATTERN_MISMATCH
+        }
+        Rule::FieldArrayLength { field, min, max } => {
+            let items = first_row(&mut mutant)?.get_mut(field)?.as_array_mut()?;
+            match (min, max) {
+                (Some(min), _) if *min > 0 => items.clear(),
+                (_, Some(max)) => {
+                    let filler = items.first().cloned().unwrap_or(Value::Null);
+                    items.resize(usize::try_from(*max).ok()? + 1, filler);
+                }
+                _ => return None,
+            }
+            codes::ARRAY_LENGTH
+        }
+        Rule::NumberRange { field, min, max } => {
+            let wrong = match (min, max) {
+                (Some(min), _) => min - 1.0,
+                (_, Some(max)) => max + 1.0,
+                _ => return None,
+            };
+            *first_row(&mut mutant)?.get_mut(field)? = json!(wrong);
+            codes::NUMBER_OUT_OF_RANGE
+        }
+        Rule::StringLength { field, min, max } => {
+            let wrong = match (min, max) {
+                (Some(min), _) if *min > 0 => String::new(),
+                (_, Some(max)) => "x".repeat(usize::try_from(*max).ok()? + 1),
+                _ => return None,
+            };
+            *first_row(&mut mutant)?.get_mut(field)? = json!(wrong);
+            codes::STRING_LENGTH
+        }
+        Rule::UniqueValues { field, .. } => {
+            let rows = mutant.as_array_mut()?;
+            let value = rows.first()?.get(field)?.clone();
+            *rows.get_mut(1)?.as_object_mut()?.get_mut(field)? = value;
+            codes::DUPLICATE_VALUE
+        }
+        Rule::KeyOrder { keys, .. } => {
+            let row = first_row(&mut mutant)?;
+            let listed: Vec<String> = row
+                .keys()
+                .filter(|key| keys.contains(key))
+                .cloned()
+                .collect();
+            if listed.len() < 2 {
+                return None;
+            }
+            // Swap the listed keys into reverse order, leaving the others in place.
+            let original = std::mem::take(row);
+            let mut reversed = listed.into_iter().rev();
+            for (key, value) in &original {
+                if keys.contains(key) {
+                    let key = reversed.next()?;
+                    let value = original[&key].clone();
+                    row.insert(key, value);
+                } else {
+                    row.insert(key.clone(), value.clone());
+                }
+            }
+            codes::KEY_ORDER
+        }
+        Rule::MaxItems { value } => {
+            let items = mutant.as_array_mut()?;
+            let filler = items.first()?.clone();
+            items.resize(usize::try_from(*value).ok()? + 1, filler);
+            codes::TOO_MANY_ITEMS
+        }
+        Rule::MinItems { value } if *value > 0 => {
+            let items = mutant.as_array_mut()?;
+            items.truncate(usize::try_from(*value - 1).ok()?);
+            codes::TOO_FEW_ITEMS
+        }
+        _ => return None,
+    };
+    Some((code, mutant))
+}
+
+fn first_row(output: &mut Value) -> Option<&mut serde_json::Map<String, Value>> {
+    match output {
+        Value::Array(rows) => rows.first_mut()?.as_object_mut(),
+        Value::Object(map) => Some(map),
+        _ => None,
+    }
+}
@@ -0,0 +1,50 @@
+use serde_json::Value;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::contract::{Normalization, UnicodeForm};
+
+/// Applies the contract's comparison options to `value`. Only strings are
+/// transformed, recursively inside arrays and objects; other values are
+/// returned unchanged.
+pub fn normalize_value(value: &Value, options: &Normalization) -> Value {
+    match value {
+        Value::String(s) => Value::String(normalize_str(s, options)),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| normalize_value(item, options))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, item)| (key.clone(), normalize_value(item, options)))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+pub fn normalize_str(s: &str, options: &Normalization) -> String {
+    let mut normalized: String = match options.unicode {
+        Some(UnicodeForm::Nfc) => s.nfc().collect(),
+        Some(UnicodeForm::Nfd) => s.nfd().collect(),
+        Some(UnicodeForm::Nfkc) => s.nfkc().collect(),
+        Some(UnicodeForm::Nfkd) => s.nfkd().collect(),
+        None => s.to_string(),
+    };
+    if options.strip_diacritics {
+        normalized = normalized
+            .nfd()
+            .filter(|c| !is_combining_mark(*c))
+            .nfc()
+            .collect();
+    }
+    if options.case_fold {
+        normalized = normalized.to_lowercase();
+    }
+    if options.collapse_whitespace {
+        normalized = normalized.split_whitespace().collect::<Vec<_>>().join(" ");
+    }
+    normalized
+}
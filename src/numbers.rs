@@ -0,0 +1,87 @@
+/// Digit grouping and decimal separator conventions for localized number strings.
+#[derive(Debug, Clone, Copy)]
+pub struct NumberLocale {
+    group: &'static [char],
+    decimal: char,
+}
+
+const POINT_DECIMAL: NumberLocale = NumberLocale {
+    group: &[','],
+    decimal: '.',
+};
+const COMMA_DECIMAL: NumberLocale = NumberLocale {
+    group: &['.'],
+    decimal: ',',
+};
+const SPACE_GROUPED: NumberLocale = NumberLocale {
+    group: &[' ', '\u{a0}', '\u{202f}'],
+    decimal: ',',
+};
+const SWISS: NumberLocale = NumberLocale {
+    group: &['\'', '\u{2019}'],
+    decimal: '.',
+};
+
+/// Looks up a locale tag (`en`, `de`, `fr-CA`, `de-CH`, ...). Region-specific
+/// conventions are matched first, then the language alone.
+pub fn number_locale(tag: &str) -> Option<NumberLocale> {
+    let tag = tag.replace('_', "-").to_ascii_lowercase();
+    if matches!(tag.as_str(), "de-ch" | "it-ch" | "fr-ch" | "rm-ch") {
+        return Some(SWISS);
+    }
+    let language = tag.split('-').next().unwrap_or_default();
+    match language {
+        "en" | "ja" | "zh" | "ko" | "he" | "th" => Some(POINT_DECIMAL),
+        "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el" | "ro" => Some(COMMA_DECIMAL),
+        "fr" | "sv" | "nb" | "no" | "fi" | "pl" | "cs" | "sk" | "ru" | "uk" | "hu" => {
+            Some(SPACE_GROUPED)
+        }
+        _ => None,
+    }
+}
+
+impl NumberLocale {
+    /// Parses `text` written in this locale's convention. Digit groups, when
+    /// used, must be well formed (`1.234.567`, not `12.34`).
+    pub fn parse(&self, text: &str) -> Option<f64> {
+        let (negative, body) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (integer, fraction) = match body.split_once(self.decimal) {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (body, None),
+        };
+
+        let groups: Vec<&str> = integer.split(|c| self.group.contains(&c)).collect();
+        let well_grouped = match groups.as_slice() {
+            [single] => !single.is_empty() && is_digits(single),
+            [first, rest @ ..] => {
+                (1..=3).contains(&first.len())
+                    && is_digits(first)
+                    && rest
+                        .iter()
+                        .all(|group| group.len() == 3 && is_digits(group))
+            }
+            [] => false,
+        };
+        if !well_grouped || fraction.is_some_and(|f| f.is_empty() || !is_digits(f)) {
+            return None;
+        }
+
+        let mut normalized = String::with_capacity(text.len());
+        if negative {
+            normalized.push('-');
+        }
+        normalized.push_str(&groups.concat());
+        if let Some(fraction) = fraction {
+            normalized.push('.');
+            normalized.push_str(fraction);
+        }
+        normalized.parse().ok()
+    }
+}
+
+fn is_digits(s: &str) -> bool {
+    s.chars().all(|c| c.is_ascii_digit())
+}
@@ -0,0 +1,24 @@
+/// Curated regex patterns that contracts can reference by name via `pattern_ref`.
+const BUILTIN_PATTERNS: &[(&str, &str)] = &[
+    (
+        "email",
+        r"^[A-Za-z0-9._%+-]+@[A-Za-z0-9](?:[A-Za-z0-9-]*[A-Za-z0-9])?(?:\.[A-Za-z0-9](?:[A-Za-z0-9-]*[A-Za-z0-9])?)*\.[A-Za-z]{2,}$",
+    ),
+    (
+        "uuid",
+        r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$",
+    ),
+    ("e164_phone", r"^\+[1-9][0-9]{1,14}$"),
+    (
+        "iso_date",
+        r"^[0-9]{4}-(?:0[1-9]|1[0-2])-(?:0[1-9]|[12][0-9]|3[01])$",
+    ),
+    ("slug", r"^[a-z0-9]+(?:-[a-z0-9]+)*$"),
+];
+
+pub fn builtin_pattern(name: &str) -> Option<&'static str> {
+    BUILTIN_PATTERNS
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, pattern)| *pattern)
+}
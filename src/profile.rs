@@ -0,0 +1,122 @@
+//! Contract-free statistics over one or more outputs: which fields rows have,
+//! of which types, how often null, how varied and how long. Useful for
+//! drafting a contract and for spotting drift before it turns into failures.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use llmc::verifier::detected_value_type;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Profile {
+    pub outputs: usize,
+    /// Objects profiled: the rows of array outputs and object outputs themselves.
+    pub rows: usize,
+    /// Array items that are not objects, which have no fields to profile.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub non_object_rows: usize,
+    pub fields: BTreeMap<String, FieldProfile>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldProfile {
+    /// Rows that have the field, including those where it is null.
+    pub present: usize,
+    /// Fraction of rows lacking the field.
+    pub missing_rate: f64,
+    /// Fraction of rows where the field is null.
+    pub null_rate: f64,
+    /// How many rows hold each JSON type.
+    pub types: BTreeMap<&'static str, usize>,
+    /// Number of distinct values.
+    pub distinct: usize,
+    /// Character lengths of strings and item counts of arrays.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<LengthStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LengthStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+}
+
+#[derive(Default)]
+struct FieldAccumulator {
+    present: usize,
+    nulls: usize,
+    types: BTreeMap<&'static str, usize>,
+    distinct: HashSet<String>,
+    lengths: Vec<usize>,
+}
+
+pub fn profile(outputs: &[Value]) -> Profile {
+    let mut rows = 0;
+    let mut non_object_rows = 0;
+    let mut fields: BTreeMap<String, FieldAccumulator> = BTreeMap::new();
+    let objects = outputs.iter().flat_map(|output| match output {
+        Value::Array(items) => items.iter().collect(),
+        _ => vec![output],
+    });
+    for object in objects {
+        let Value::Object(map) = object else {
+            non_object_rows += 1;
+            continue;
+        };
+        rows += 1;
+        for (key, value) in map {
+            let field = fields.entry(key.clone()).or_default();
+            field.present += 1;
+            *field.types.entry(detected_value_type(value)).or_default() += 1;
+            field.distinct.insert(value.to_string());
+            match value {
+                Value::Null => field.nulls += 1,
+                Value::String(text) => field.lengths.push(text.chars().count()),
+                Value::Array(items) => field.lengths.push(items.len()),
+                _ => {}
+            }
+        }
+    }
+
+    let rate = |count: usize| {
+        if rows == 0 {
+            0.0
+        } else {
+            count as f64 / rows as f64
+        }
+    };
+    Profile {
+        outputs: outputs.len(),
+        rows,
+        non_object_rows,
+        fields: fields
+            .into_iter()
+            .map(|(key, field)| {
+                let profile = FieldProfile {
+                    present: field.present,
+                    missing_rate: rate(rows - field.present),
+                    null_rate: rate(field.nulls),
+                    types: field.types,
+                    distinct: field.distinct.len(),
+                    length: length_stats(&field.lengths),
+                };
+                (key, profile)
+            })
+            .collect(),
+    }
+}
+
+fn length_stats(lengths: &[usize]) -> Option<LengthStats> {
+    Some(LengthStats {
+        min: *lengths.iter().min()?,
+        max: *lengths.iter().max()?,
+        mean: lengths.iter().sum::<usize>() as f64 / lengths.len() as f64,
+    })
+}
+
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
@@ -0,0 +1,56 @@
+//! Remediation prompts: a user-supplied minijinja template rendered with a
+//! verdict's violation data, producing the follow-up message sent back to the
+//! model.
+
+use std::collections::BTreeSet;
+
+use minijinja::Environment;
+use serde_json::Value;
+
+use crate::diff::{violation_rows, violations};
+
+/// Renders `template` with the verdict `document` as context. On top of the
+/// verdict's own keys, templates can use:
+///
+/// - `violations`: every violation, whatever the layout; in batch documents
+///   each carries the `output` it belongs to,
+/// - `fields`: the distinct fields with violations,
+/// - `failed_rows`: the distinct row indexes with violations.
+pub fn render_remediation(template: &str, document: &Value) -> Result<String, minijinja::Error> {
+    let mut env = Environment::new();
+    env.set_keep_trailing_newline(true);
+    env.add_template("remediation", template)?;
+
+    let verdicts: Vec<&Value> = match document.get("verdicts").and_then(Value::as_array) {
+        Some(verdicts) => verdicts.iter().collect(),
+        None => vec![document],
+    };
+    let all_violations: Vec<Value> = verdicts
+        .iter()
+        .flat_map(|verdict| {
+            violations(verdict).into_iter().map(|violation| {
+                let mut violation = violation.clone();
+                if let (Some(output), Value::Object(map)) = (verdict.get("output"), &mut violation)
+                {
+                    map.insert("output".to_string(), output.clone());
+                }
+                violation
+            })
+        })
+        .collect();
+    let fields: BTreeSet<&str> = all_violations
+        .iter()
+        .filter_map(|violation| violation.get("field").and_then(Value::as_str))
+        .filter(|field| !field.is_empty())
+        .collect();
+    let fields = serde_json::json!(fields);
+    let failed_rows = serde_json::json!(violation_rows(&all_violations));
+
+    let mut context = document.clone();
+    if let Value::Object(map) = &mut context {
+        map.insert("violations".to_string(), Value::from(all_violations));
+        map.insert("fields".to_string(), fields);
+        map.insert("failed_rows".to_string(), failed_rows);
+    }
+    env.get_template("remediation")?.render(context)
+}
@@ -0,0 +1,241 @@
+//! Verdicts as CI test reports: SARIF for code scanning annotations (GitHub
+//! and others) and JUnit XML for the test report views of Jenkins, GitLab and
+//! most CI servers. Both identify the run by the contract's name and version.
+
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use llmc::contract::Severity;
+use llmc::verifier::{Verdict, VerdictStatus, Violation};
+
+use crate::lsp::{encode_path, file_uri};
+use crate::meta::RunMeta;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// A verdict with the file it is about and the name it is reported under,
+/// e.g. `results.jsonl:3` for a JSONL record.
+pub struct ReportedVerdict<'a> {
+    pub path: &'a Path,
+    pub label: String,
+    pub verdict: &'a Verdict,
+}
+
+/// A SARIF 2.1.0 log with one run, whose results are the violations of every
+/// verdict. Rules are identified by violation code.
+pub fn sarif(meta: &RunMeta, verdicts: &[ReportedVerdict]) -> Value {
+    let mut rules: Vec<Value> = Vec::new();
+    let mut results = Vec::new();
+    for reported in verdicts {
+        for violation in &reported.verdict.violations {
+            if !rules.iter().any(|rule| rule["id"] == violation.code) {
+                rules.push(json!({ "id": violation.code, "name": rule_name(violation) }));
+            }
+            results.push(sarif_result(reported.path, violation));
+        }
+    }
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": meta.tool,
+                    "version": meta.tool_version,
+                    "rules": rules
+                }
+            },
+            "automationDetails": { "id": contract_id(meta) },
+            "columnKind": "unicodeCodePoints",
+            "results": results
+        }]
+    })
+}
+
+fn sarif_result(path: &Path, violation: &Violation) -> Value {
+    // Output-level violations and runtime errors point at the whole file.
+    let region = match (violation.position, violation.end_position) {
+        (Some(start), Some(end)) => json!({
+            "startLine": start.line,
+            "startColumn": start.column,
+            "endLine": end.line,
+            "endColumn": end.column
+        }),
+        (Some(start), None) => json!({ "startLine": start.line, "startColumn": start.column }),
+        _ => json!({ "startLine": 1 }),
+    };
+    let mut properties = serde_json::Map::new();
+    properties.insert("rule".to_string(), json!(rule_name(violation)));
+    for (key, value) in [
+        ("field", violation.field.as_ref().map(|field| json!(field))),
+        ("row", violation.row.map(|row| json!(row))),
+        ("expected", violation.expected.clone()),
+        ("actual", violation.actual.clone()),
+    ] {
+        if let Some(value) = value {
+            properties.insert(key.to_string(), value);
+        }
+    }
+    json!({
+        "ruleId": violation.code,
+        "level": match violation.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "note",
+        },
+        "message": { "text": violation.detail },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": artifact_uri(path) },
+                "region": region
+            }
+        }],
+        "properties": properties
+    })
+}
+
+/// A JUnit XML report with one test case per verdict. Failing verdicts carry a
+/// `<failure>` (an `<error>` for runtime errors and invalid contracts)
+/// listing every violation; violations of passing verdicts, such as
+/// warnings, go to `<system-out>`.
+pub fn junit(meta: &RunMeta, verdicts: &[ReportedVerdict]) -> String {
+    let suite = xml_escape(&contract_id(meta));
+    let failures = verdicts
+        .iter()
+        .filter(|reported| fails(reported.verdict) && !is_error(reported.verdict))
+        .count();
+    let errors = verdicts
+        .iter()
+        .filter(|reported| is_error(reported.verdict))
+        .count();
+    let time = meta.duration_ms as f64 / 1000.0;
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time}\">\n",
+        meta.tool,
+        verdicts.len()
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{suite}\" tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time}\" timestamp=\"{}\">\n",
+        verdicts.len(),
+        meta.started_at
+    ));
+    for reported in verdicts {
+        let verdict = reported.verdict;
+        let name = xml_escape(&reported.label);
+        if verdict.violations.is_empty() {
+            xml.push_str(&format!(
+                "    <testcase classname=\"{suite}\" name=\"{name}\"/>\n"
+            ));
+            continue;
+        }
+        xml.push_str(&format!(
+            "    <testcase classname=\"{suite}\" name=\"{name}\">\n"
+        ));
+        let listing = xml_escape(
+            &verdict
+                .violations
+                .iter()
+                .map(describe)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+        if fails(verdict) {
+            let element = if is_error(verdict) {
+                "error"
+            } else {
+                "failure"
+            };
+            let first = &verdict.violations[0];
+            let message = match verdict.violations.len() {
+                1 => first.detail.clone(),
+                count => format!("{count} violations; first: {}", first.detail),
+            };
+            xml.push_str(&format!(
+                "      <{element} type=\"{}\" message=\"{}\">{listing}</{element}>\n",
+                first.code,
+                xml_escape(&message)
+            ));
+        } else {
+            xml.push_str(&format!("      <system-out>{listing}</system-out>\n"));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>");
+    xml
+}
+
+fn fails(verdict: &Verdict) -> bool {
+    matches!(verdict.status, VerdictStatus::Fail | VerdictStatus::Partial)
+}
+
+// Runtime errors and invalid contracts say nothing about the output.
+fn is_error(verdict: &Verdict) -> bool {
+    verdict
+        .violations
+        .iter()
+        .any(|violation| matches!(violation.rule_name.as_str(), "Runtime" | "InvalidContract"))
+}
+
+// `[LLMC301] allowed_values 'status' (row 2): <message> Expected ..., found ...`
+fn describe(violation: &Violation) -> String {
+    let mut line = format!("[{}] {}", violation.code, rule_name(violation));
+    if let Some(field) = violation.field.as_deref().filter(|field| !field.is_empty()) {
+        line.push_str(&format!(" '{field}'"));
+    }
+    if let Some(row) = violation.row {
+        line.push_str(&format!(" (row {row})"));
+    }
+    line.push_str(&format!(": {}", violation.detail));
+    if let Some(expected) = &violation.expected {
+        line.push_str(&format!(" Expected {expected}"));
+        match &violation.actual {
+            Some(actual) => line.push_str(&format!(", found {actual}.")),
+            None => line.push('.'),
+        }
+    }
+    line
+}
+
+fn rule_name(violation: &Violation) -> &str {
+    violation.rule.as_deref().unwrap_or(&violation.rule_name)
+}
+
+// `user_list@2`, or the contract path for unnamed contracts.
+fn contract_id(meta: &RunMeta) -> String {
+    let name = meta
+        .contract
+        .name
+        .clone()
+        .unwrap_or_else(|| meta.contract.path.clone());
+    match meta.contract.version {
+        Some(version) => format!("{name}@{version}"),
+        None => name,
+    }
+}
+
+// Relative paths stay relative, so code scanning resolves them against the
+// repository root.
+fn artifact_uri(path: &Path) -> String {
+    if path.is_absolute() {
+        file_uri(path)
+    } else {
+        let path = path.strip_prefix(".").unwrap_or(path);
+        encode_path(&path.to_string_lossy().replace('\\', "/"))
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,73 @@
+//! Snapshot testing for verdicts: lock in exactly what the verifier reports
+//! for a contract and get a readable diff when that changes.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+use similar::TextDiff;
+
+/// Set to `1` to rewrite snapshots instead of comparing against them.
+pub const UPDATE_ENV: &str = "LLMC_UPDATE_SNAPSHOTS";
+
+// Run metadata that differs between otherwise identical runs.
+const VOLATILE_META_KEYS: [&str; 3] = ["started_at", "finished_at", "duration_ms"];
+
+/// `verdict` as JSON without timestamps and durations and with violations in
+/// a stable order. Takes a [`Verdict`](crate::verifier::Verdict) or a verdict
+/// document as printed by the CLI, batch and row layouts included.
+pub fn normalize_verdict(verdict: &impl Serialize) -> Value {
+    let mut verdict = serde_json::to_value(verdict).expect("serialize verdict");
+    normalize(&mut verdict);
+    verdict
+}
+
+fn normalize(verdict: &mut Value) {
+    let Value::Object(map) = verdict else {
+        return;
+    };
+    if let Some(Value::Object(meta)) = map.get_mut("meta") {
+        for key in VOLATILE_META_KEYS {
+            meta.remove(key);
+        }
+    }
+    if let Some(Value::Array(violations)) = map.get_mut("violations") {
+        violations.sort_by_cached_key(Value::to_string);
+    }
+    for key in ["rows", "verdicts"] {
+        if let Some(Value::Array(nested)) = map.get_mut(key) {
+            nested.iter_mut().for_each(normalize);
+        }
+    }
+}
+
+/// Compares the normalized `verdict` with the snapshot stored at `path`,
+/// panicking with a line diff when they differ. The snapshot is written
+/// instead when it does not exist yet or when [`UPDATE_ENV`] is `1`.
+pub fn assert_verdict_snapshot(verdict: &impl Serialize, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    let actual = serde_json::to_string_pretty(&normalize_verdict(verdict))
+        .expect("serialize verdict")
+        + "\n";
+    let update = env::var(UPDATE_ENV).is_ok_and(|value| value == "1");
+    if update || !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("create snapshot directory");
+        }
+        fs::write(path, actual).expect("write snapshot");
+        return;
+    }
+    let expected = fs::read_to_string(path).expect("read snapshot");
+    if expected != actual {
+        let diff = TextDiff::from_lines(&expected, &actual)
+            .unified_diff()
+            .header("snapshot", "verdict")
+            .to_string();
+        panic!(
+            "verdict does not match snapshot {}; rerun with {UPDATE_ENV}=1 to accept it\n{diff}",
+            path.display()
+        );
+    }
+}
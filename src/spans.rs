@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+/// Where a value starts in the original output file.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct Position {
+    /// Byte offset from the start of the file.
+    pub offset: usize,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+}
+
+/// One step of the path from the root to a value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Index(usize),
+    Key(String),
+}
+
+/// Start and end offsets of every value in a JSON document, keyed by path.
+/// serde_json drops positions once it has built a `Value`, so the source is
+/// scanned a second time.
+#[derive(Debug, Default)]
+pub struct SpanIndex<'a> {
+    source: &'a str,
+    spans: HashMap<Vec<PathSegment>, (usize, usize)>,
+    /// Byte offset where each line starts, so positions are found by binary
+    /// search instead of rescanning the source.
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SpanIndex<'a> {
+    /// Scans `source`. Returns `None` when it is not valid JSON, which cannot
+    /// happen for outputs serde_json already parsed.
+    pub fn scan(source: &'a str) -> Option<Self> {
+        Self::scan_from(source, 0)
+    }
+
+    /// Scans the JSON value starting at byte `offset` of `source`, such as
+    /// one extracted from surrounding text. Positions count from the start
+    /// of `source`.
+    pub fn scan_from(source: &'a str, offset: usize) -> Option<Self> {
+        let mut scanner = Scanner {
+            bytes: source.as_bytes(),
+            pos: offset,
+            path: Vec::new(),
+            spans: HashMap::new(),
+        };
+        scanner.value()?;
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(idx, _)| idx + 1))
+            .collect();
+        Some(SpanIndex {
+            source,
+            spans: scanner.spans,
+            line_starts,
+        })
+    }
+
+    /// Whether there is a value at `path`.
+    pub fn contains(&self, path: &[PathSegment]) -> bool {
+        self.spans.contains_key(path)
+    }
+
+    /// Where the value at `path` starts.
+    pub fn position(&self, path: &[PathSegment]) -> Option<Position> {
+        let (start, _) = *self.spans.get(path)?;
+        Some(self.position_of(start))
+    }
+
+    /// Just past the end of the value at `path`.
+    pub fn end_position(&self, path: &[PathSegment]) -> Option<Position> {
+        let (_, end) = *self.spans.get(path)?;
+        Some(self.position_of(end))
+    }
+
+    fn position_of(&self, offset: usize) -> Position {
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let line_start = self.line_starts[line - 1];
+        Position {
+            offset,
+            line,
+            column: self.source[line_start..offset].chars().count() + 1,
+        }
+    }
+}
+
+/// The line and column of byte `offset` in `source`.
+pub(crate) fn position_at(source: &str, offset: usize) -> Position {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    Position {
+        offset,
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
+
+struct Scanner<'s> {
+    bytes: &'s [u8],
+    pos: usize,
+    path: Vec<PathSegment>,
+    spans: HashMap<Vec<PathSegment>, (usize, usize)>,
+}
+
+impl Scanner<'_> {
+    fn value(&mut self) -> Option<()> {
+        self.skip_whitespace();
+        let start = self.pos;
+        self.value_body()?;
+        self.spans.insert(self.path.clone(), (start, self.pos));
+        Some(())
+    }
+
+    fn value_body(&mut self) -> Option<()> {
+        match self.peek()? {
+            b'{' => self.object(),
+            b'[' => self.array(),
+            b'"' => self.string().map(drop),
+            _ => {
+                let start = self.pos;
+                while self
+                    .peek()
+                    .is_some_and(|byte| !matches!(byte, b',' | b']' | b'}') && !is_space(byte))
+                {
+                    self.pos += 1;
+                }
+                (self.pos > start).then_some(())
+            }
+        }
+    }
+
+    fn object(&mut self) -> Option<()> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.eat(b'}') {
+            return Some(());
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            if !self.eat(b':') {
+                return None;
+            }
+            self.path.push(PathSegment::Key(key));
+            self.value()?;
+            self.path.pop();
+            self.skip_whitespace();
+            if self.eat(b'}') {
+                return Some(());
+            }
+            if !self.eat(b',') {
+                return None;
+            }
+        }
+    }
+
+    fn array(&mut self) -> Option<()> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.eat(b']') {
+            return Some(());
+        }
+        for index in 0.. {
+            self.path.push(PathSegment::Index(index));
+            self.value()?;
+            self.path.pop();
+            self.skip_whitespace();
+            if self.eat(b']') {
+                return Some(());
+            }
+            if !self.eat(b',') {
+                return None;
+            }
+        }
+        None
+    }
+
+    // Reads a string literal and returns its decoded contents.
+    fn string(&mut self) -> Option<String> {
+        if !self.eat(b'"') {
+            return None;
+        }
+        let start = self.pos;
+        let mut escaped = false;
+        loop {
+            match self.peek()? {
+                b'"' if !escaped => break,
+                b'\\' => escaped = !escaped,
+                _ => escaped = false,
+            }
+            self.pos += 1;
+        }
+        let literal = &self.bytes[start - 1..=self.pos];
+        self.pos += 1;
+        serde_json::from_slice(literal).ok()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(is_space) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let matched = self.peek() == Some(byte);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+}
+
+fn is_space(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r')
+}
@@ -0,0 +1,204 @@
+//! [`proptest`] strategies producing values a contract permits, so consumers
+//! can property-test their downstream parsing against every shape the
+//! contract lets through. Requires the `proptest` feature.
+
+use std::collections::BTreeMap;
+
+use proptest::collection::vec;
+use proptest::option;
+use proptest::prelude::*;
+use proptest::sample::select;
+use proptest::string::string_regex;
+use serde_json::{json, Map, Value};
+
+use crate::contract::{Contract, OutputType, Rule, ValueType};
+use crate::examples::rule_example;
+
+// Rows allowed on top of the contract's `min_items`, and items on top of a
+// `field_array_length` minimum without a maximum.
+const EXTRA_ITEMS: usize = 8;
+// Width of the values drawn past an open `number_range` bound; the sampler
+// needs a finite range.
+const RANGE_SPAN: f64 = 1e6;
+
+/// A strategy for outputs of `contract`: values of its `output_type` whose
+/// rows hold the fields its rules name. Each field draws from its most
+/// constraining rule: `const`, then `allowed_values`, `regex`,
+/// `number_range`, `string_length`, `field_type`, `field_array_length` and
+/// finally the rule's example. Fields only
+/// constrained when present (`allowed_values`, `const`, `regex`,
+/// `field_array_length`) are sometimes left out unless the contract is
+/// strict. Rows list the keys of a `key_order` rule first, in its order.
+///
+/// Output-level and cross-row rules other than `min_items` and `max_items` are not
+/// modelled; filter with [`crate::verifier::verify`] when the contract
+/// relies on them.
+pub fn output_strategy(contract: &Contract) -> BoxedStrategy<Value> {
+    let mut fields: BTreeMap<String, Vec<&Rule>> = BTreeMap::new();
+    let mut min_items = 0;
+    let mut max_items = usize::MAX;
+    let mut order: &[String] = &[];
+    for (_, spec) in contract.rule_specs() {
+        match &spec.rule {
+            Rule::MinItems { value } => {
+                min_items = min_items.max(usize::try_from(*value).unwrap_or(usize::MAX))
+            }
+            Rule::MaxItems { value } => {
+                max_items = max_items.min(usize::try_from(*value).unwrap_or(usize::MAX))
+            }
+            Rule::KeyOrder { keys, .. } => {
+                order = keys;
+                for key in keys {
+                    fields.entry(key.clone()).or_default().push(&spec.rule);
+                }
+            }
+            rule => {
+                if let Some(field) = rule.field() {
+                    fields.entry(field.to_string()).or_default().push(rule);
+                }
+            }
+        }
+    }
+
+    let field_strategies: Vec<BoxedStrategy<Option<Value>>> = fields
+        .values()
+        .map(|rules| {
+            let value = field_strategy(contract, rules);
+            if !contract.strict && rules.iter().all(|rule| constrains_if_present(rule)) {
+                option::of(value).boxed()
+            } else {
+                value.prop_map(Some).boxed()
+            }
+        })
+        .collect();
+    let names: Vec<String> = fields.into_keys().collect();
+    // Keys named by `key_order` come first, in its order.
+    let mut positions: Vec<usize> = (0..names.len()).collect();
+    positions.sort_by_key(|&idx| {
+        order
+            .iter()
+            .position(|key| *key == names[idx])
+            .unwrap_or(order.len())
+    });
+    let row = field_strategies.prop_map(move |values| {
+        let row: Map<String, Value> = positions
+            .iter()
+            .filter_map(|&idx| Some((names[idx].clone(), values[idx].clone()?)))
+            .collect();
+        Value::Object(row)
+    });
+    match contract.output_type {
+        OutputType::Object => row.boxed(),
+        OutputType::Array => {
+            let most = min_items.saturating_add(EXTRA_ITEMS).min(max_items);
+            vec(row, min_items.min(most)..=most)
+                .prop_map(Value::Array)
+                .boxed()
+        }
+    }
+}
+
+fn field_strategy(contract: &Contract, rules: &[&Rule]) -> BoxedStrategy<Value> {
+    let find = |matches: fn(&Rule) -> bool| rules.iter().copied().find(|rule| matches(rule));
+
+    if let Some(Rule::Const { value, .. }) = find(|rule| matches!(rule, Rule::Const { .. })) {
+        return Just(value.clone()).boxed();
+    }
+    if let Some(Rule::AllowedValues { values, .. }) =
+        find(|rule| matches!(rule, Rule::AllowedValues { values, .. } if !values.is_empty()))
+    {
+        return select(values.clone()).boxed();
+    }
+    if let Some(Rule::Regex {
+        pattern,
+        pattern_ref,
+        ..
+    }) = find(|rule| matches!(rule, Rule::Regex { .. }))
+    {
+        let strategy = contract
+            .resolve_pattern(pattern.as_deref(), pattern_ref.as_deref())
+            .and_then(|pattern| string_regex(pattern).ok());
+        if let Some(strategy) = strategy {
+            return strategy.prop_map(Value::String).boxed();
+        }
+    }
+    if let Some(Rule::NumberRange { min, max, .. }) =
+        find(|rule| matches!(rule, Rule::NumberRange { .. }))
+    {
+        let low = min.unwrap_or_else(|| max.unwrap_or(0.0) - RANGE_SPAN);
+        let high = max.unwrap_or(low + RANGE_SPAN).max(low);
+        return (low..=high).prop_map(|number| json!(number)).boxed();
+    }
+    if let Some(Rule::StringLength { min, max, .. }) =
+        find(|rule| matches!(rule, Rule::StringLength { .. }))
+    {
+        let min = min.map_or(0, |min| usize::try_from(min).unwrap_or(usize::MAX));
+        let max = max.map_or(min.saturating_add(EXTRA_ITEMS), |max| {
+            usize::try_from(max).unwrap_or(usize::MAX).max(min)
+        });
+        return vec(any::<char>(), min..=max)
+            .prop_map(|chars| Value::String(chars.into_iter().collect()))
+            .boxed();
+    }
+    if let Some(Rule::FieldType { expected, .. }) =
+        find(|rule| matches!(rule, Rule::FieldType { .. }))
+    {
+        if *expected != ValueType::Array {
+            return typed_strategy(expected);
+        }
+    }
+    if let Some(Rule::FieldArrayLength { min, max, .. }) =
+        find(|rule| matches!(rule, Rule::FieldArrayLength { .. }))
+    {
+        let min = min.map_or(0, |min| usize::try_from(min).unwrap_or(usize::MAX));
+        let max = max.map_or(min.saturating_add(EXTRA_ITEMS), |max| {
+            usize::try_from(max).unwrap_or(usize::MAX).max(min)
+        });
+        return vec(any::<String>().prop_map(Value::String), min..=max)
+            .prop_map(Value::Array)
+            .boxed();
+    }
+    // A required field's default satisfies nothing but its presence.
+    let (required, others): (Vec<&Rule>, Vec<&Rule>) = rules
+        .iter()
+        .partition(|rule| matches!(rule, Rule::RequiredField { .. }));
+    match others
+        .into_iter()
+        .chain(required)
+        .find_map(|rule| rule_example(contract, rule))
+    {
+        Some(example) => Just(example).boxed(),
+        None => any::<String>().prop_map(Value::String).boxed(),
+    }
+}
+
+fn typed_strategy(value_type: &ValueType) -> BoxedStrategy<Value> {
+    match value_type {
+        ValueType::String => any::<String>().prop_map(Value::String).boxed(),
+        ValueType::Number => prop_oneof![
+            any::<i64>().prop_map(|number| json!(number)),
+            proptest::num::f64::NORMAL.prop_map(|number| json!(number)),
+        ]
+        .boxed(),
+        ValueType::Boolean => any::<bool>().prop_map(Value::Bool).boxed(),
+        ValueType::Object => Just(json!({})).boxed(),
+        ValueType::Array => Just(json!([])).boxed(),
+        ValueType::Null => Just(Value::Null).boxed(),
+    }
+}
+
+// Rules that pass when their field is absent, outside strict mode.
+fn constrains_if_present(rule: &Rule) -> bool {
+    matches!(
+        rule,
+        Rule::AllowedValues { .. }
+            | Rule::Const { .. }
+            | Rule::Regex { .. }
+            | Rule::FieldArrayLength { .. }
+            | Rule::NumberRange { .. }
+            | Rule::StringLength { .. }
+            | Rule::FieldComparison { .. }
+            | Rule::UniqueValues { .. }
+            | Rule::KeyOrder { exact: false, .. }
+    )
+}
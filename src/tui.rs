@@ -0,0 +1,292 @@
+//! Terminal UI for browsing the violations of a saved verdict: filter by
+//! rule, field, row or free text, inspect offending values, and switch
+//! between the outputs of a batch run.
+
+use std::io;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use serde_json::Value;
+
+use crate::diff::violations;
+
+// Violations skipped by PageUp/PageDown.
+const PAGE: usize = 20;
+// Violation keys a `key:value` filter term can match.
+const FILTER_KEYS: [&str; 5] = ["rule", "field", "row", "code", "severity"];
+const HELP: &str =
+    " q quit  / filter  j/k move  PgUp/PgDn page  n/p next/prev output  [/] scroll detail ";
+
+struct Output<'a> {
+    label: String,
+    status: &'a str,
+    violations: Vec<&'a Value>,
+}
+
+struct Browser<'a> {
+    outputs: Vec<Output<'a>>,
+    output: usize,
+    filter: String,
+    editing: bool,
+    /// Indexes of the current output's violations that match the filter.
+    matches: Vec<usize>,
+    list: ListState,
+    detail_scroll: u16,
+}
+
+/// Whether `document` has anything to browse: a single verdict, or a batch
+/// document with at least one.
+pub fn has_verdicts(document: &Value) -> bool {
+    document
+        .get("verdicts")
+        .and_then(Value::as_array)
+        .is_none_or(|verdicts| !verdicts.is_empty())
+}
+
+/// Runs the UI on the terminal until the user quits. `label` names the
+/// output of a single verdict; batch documents use their outputs' paths.
+pub fn browse(document: &Value, label: &str) -> io::Result<()> {
+    let mut browser = Browser::new(document, label);
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut browser);
+    ratatui::restore();
+    result
+}
+
+fn run(terminal: &mut DefaultTerminal, browser: &mut Browser) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| browser.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !browser.handle_key(key) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<'a> Browser<'a> {
+    fn new(document: &'a Value, label: &str) -> Self {
+        let verdicts: Vec<(String, &Value)> =
+            match document.get("verdicts").and_then(Value::as_array) {
+                Some(verdicts) => verdicts
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, verdict)| {
+                        let label = verdict
+                            .get("output")
+                            .and_then(Value::as_str)
+                            .map_or_else(|| format!("output {}", idx + 1), str::to_string);
+                        (label, verdict)
+                    })
+                    .collect(),
+                None => vec![(label.to_string(), document)],
+            };
+        let mut browser = Browser {
+            outputs: verdicts
+                .into_iter()
+                .map(|(label, verdict)| Output {
+                    label,
+                    status: verdict
+                        .get("status")
+                        .and_then(Value::as_str)
+                        .unwrap_or("unknown"),
+                    violations: violations(verdict),
+                })
+                .collect(),
+            output: 0,
+            filter: String::new(),
+            editing: false,
+            matches: Vec::new(),
+            list: ListState::default(),
+            detail_scroll: 0,
+        };
+        browser.apply_filter();
+        browser
+    }
+
+    fn apply_filter(&mut self) {
+        let terms: Vec<String> = self
+            .filter
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        let Some(output) = self.outputs.get(self.output) else {
+            return;
+        };
+        self.matches = output
+            .violations
+            .iter()
+            .enumerate()
+            .filter(|(_, violation)| terms.iter().all(|term| matches_term(violation, term)))
+            .map(|(idx, _)| idx)
+            .collect();
+        self.list.select((!self.matches.is_empty()).then_some(0));
+        self.detail_scroll = 0;
+    }
+
+    fn selected(&self) -> Option<&'a Value> {
+        let idx = *self.matches.get(self.list.selected()?)?;
+        Some(self.outputs[self.output].violations[idx])
+    }
+
+    fn select(&mut self, idx: usize) {
+        if !self.matches.is_empty() {
+            self.list.select(Some(idx.min(self.matches.len() - 1)));
+            self.detail_scroll = 0;
+        }
+    }
+
+    fn switch_output(&mut self, forward: bool) {
+        let count = self.outputs.len();
+        self.output = if forward {
+            (self.output + 1) % count
+        } else {
+            (self.output + count - 1) % count
+        };
+        self.apply_filter();
+    }
+
+    // Returns `false` when the user quits.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if self.editing {
+            match key.code {
+                KeyCode::Enter => self.editing = false,
+                KeyCode::Esc => {
+                    self.editing = false;
+                    self.filter.clear();
+                    self.apply_filter();
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.apply_filter();
+                }
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.apply_filter();
+                }
+                _ => {}
+            }
+            return true;
+        }
+        let current = self.list.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('/') => self.editing = true,
+            KeyCode::Down | KeyCode::Char('j') => self.select(current + 1),
+            KeyCode::Up | KeyCode::Char('k') => self.select(current.saturating_sub(1)),
+            KeyCode::PageDown => self.select(current + PAGE),
+            KeyCode::PageUp => self.select(current.saturating_sub(PAGE)),
+            KeyCode::Home | KeyCode::Char('g') => self.select(0),
+            KeyCode::End | KeyCode::Char('G') => self.select(usize::MAX),
+            KeyCode::Tab | KeyCode::Char('n') => self.switch_output(true),
+            KeyCode::BackTab | KeyCode::Char('p') => self.switch_output(false),
+            KeyCode::Char(']') => self.detail_scroll = self.detail_scroll.saturating_add(1),
+            KeyCode::Char('[') => self.detail_scroll = self.detail_scroll.saturating_sub(1),
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list, detail] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(body);
+
+        let output = &self.outputs[self.output];
+        frame.render_widget(
+            Line::from(format!(
+                " {} ({}/{}) | {} | {} of {} violations",
+                output.label,
+                self.output + 1,
+                self.outputs.len(),
+                output.status.to_uppercase(),
+                self.matches.len(),
+                output.violations.len()
+            ))
+            .bold(),
+            header,
+        );
+        self.draw_list(frame, list);
+        self.draw_detail(frame, detail);
+        let footer_text = if self.editing || !self.filter.is_empty() {
+            format!(
+                " filter: {}{}",
+                self.filter,
+                if self.editing { "_" } else { "" }
+            )
+        } else {
+            HELP.to_string()
+        };
+        frame.render_widget(Line::from(footer_text).reversed(), footer);
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let output = &self.outputs[self.output];
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|&idx| ListItem::new(summary(output.violations[idx])))
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(" Violations "))
+            .highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(list, area, &mut self.list);
+    }
+
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let text = self
+            .selected()
+            .map(|violation| serde_json::to_string_pretty(violation).expect("serialize violation"))
+            .unwrap_or_else(|| "No matching violations.".to_string());
+        let detail = Paragraph::new(text)
+            .block(Block::bordered().title(" Detail "))
+            .wrap(Wrap { trim: false })
+            .scroll((self.detail_scroll, 0));
+        frame.render_widget(detail, area);
+    }
+}
+
+// `row 12  LLMC101  id  Missing required field ...`
+fn summary(violation: &Value) -> String {
+    let text = |key: &str| violation.get(key).and_then(Value::as_str).unwrap_or("");
+    let row = violation
+        .get("row")
+        .and_then(Value::as_u64)
+        .map_or_else(|| "-".to_string(), |row| row.to_string());
+    format!(
+        "{row:>5}  {}  {}  {}",
+        text("code"),
+        text("field"),
+        text("message")
+    )
+}
+
+// `key:value` terms compare one violation key (or any of `rows` for
+// `row:`), other terms search the violation's JSON; both ignore case.
+fn matches_term(violation: &Value, term: &str) -> bool {
+    if let Some((key, wanted)) = term.split_once(':') {
+        if FILTER_KEYS.contains(&key) {
+            let equals = |value: &Value| match value {
+                Value::String(text) => text.to_lowercase() == wanted,
+                value => serde_json::from_str::<Value>(wanted).is_ok_and(|wanted| wanted == *value),
+            };
+            let rows_match = key == "row"
+                && violation
+                    .get("rows")
+                    .and_then(Value::as_array)
+                    .is_some_and(|rows| rows.iter().any(equals));
+            return violation.get(key).is_some_and(equals) || rows_match;
+        }
+    }
+    violation.to_string().to_lowercase().contains(term)
+}
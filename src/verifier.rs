@@ -1,19 +1,45 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::fs;
-use std::io;
+use std::io::{self, Read};
 use std::path::Path;
 
+use rayon::prelude::*;
 use regex::Regex;
 use serde::Serialize;
-use serde_json::Value;
+use serde_json::{json, Value};
 
-use crate::contract::{Contract, OutputType, Rule, ValueType};
+use crate::checksum;
+use crate::codes;
+use crate::contract::{
+    BoundingBox, CheckDigitScheme, ComparisonOperator, Contract, ContractPack, GroupMode,
+    Normalization, OutputFormat, OutputType, Rule, RuleGroup, RuleSpec, Severity, ValueType,
+};
+use crate::datetime::{self, DatetimeFormat};
+use crate::examples::rule_example;
+use crate::extract::{extract_json, Extraction};
+use crate::hints::rule_hint;
+use crate::messages::{is_known_locale, Messages};
+use crate::normalize::normalize_value;
+use crate::numbers::{number_locale, NumberLocale};
+use crate::spans::{PathSegment, Position, SpanIndex};
+
+mod path;
+
+const DEFAULT_RULE_WEIGHT: f64 = 1.0;
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum VerdictStatus {
     Pass,
+    /// Row mode only: enough rows passed, but not all of them.
+    PartialPass,
+    /// Row mode only: the row pass rate lies between `row_fail_threshold`
+    /// and `row_pass_threshold`, so the output needs review.
+    Partial,
     Fail,
 }
 
@@ -21,6 +47,8 @@ pub enum VerdictStatus {
 pub struct Violation {
     pub rule_name: String,
     pub detail: String,
+    /// Stable code identifying the rule kind and failure mode, e.g. `LLMC101`.
+    pub code: &'static str,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub field: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -29,12 +57,129 @@ pub struct Violation {
     pub expected: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub actual: Option<Value>,
+    /// Human-readable fix, e.g. the default value of a missing required field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    /// How to satisfy the rule, generated per rule kind or taken from the contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    /// A minimal value that would satisfy the rule, shown next to `actual`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Redacted, truncated JSON of the offending row, when excerpts are requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub excerpt: Option<String>,
+    /// Index of the row the violation was raised for, for array outputs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row: Option<usize>,
+    /// Rows of violations merged into this one by `dedupe_violations`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rows: Vec<usize>,
+    /// Where the offending value starts in the output file, when verified
+    /// from a file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+    /// Just past the end of the offending value, for highlighting it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_position: Option<Position>,
+    pub severity: Severity,
+    /// `false` for violations of warn-only rules, which never fail the verdict.
+    #[serde(skip_serializing_if = "is_enforced")]
+    pub enforced: bool,
+    /// Values of other fields the message quotes, by field, so that
+    /// redaction finds them too.
+    #[serde(skip)]
+    pub(crate) quoted: Vec<(String, Value)>,
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+fn is_enforced(enforced: &bool) -> bool {
+    *enforced
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
 pub struct Verdict {
     pub status: VerdictStatus,
     pub violations: Vec<Violation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pass_threshold: Option<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<GroupSummary>,
+    /// One entry per contract rule, passed or not.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<CheckSummary>,
+    /// Per-row results for array outputs.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rows: Vec<RowSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_pass_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_pass_threshold: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_fail_threshold: Option<f64>,
+    /// Where the JSON was found when it had to be extracted from text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extraction: Option<Extraction>,
+}
+
+impl Verdict {
+    /// Whether the verdict fails with an enforced violation at least as severe
+    /// as `threshold`. With `Severity::Info` this is any failing verdict.
+    pub fn fails_at(&self, threshold: Severity) -> bool {
+        self.status == VerdictStatus::Fail && self.has_enforced_at(threshold)
+    }
+
+    /// Whether the verdict is `partial` with an enforced violation at least
+    /// as severe as `threshold`, like [`Verdict::fails_at`].
+    pub fn partial_at(&self, threshold: Severity) -> bool {
+        self.status == VerdictStatus::Partial && self.has_enforced_at(threshold)
+    }
+
+    fn has_enforced_at(&self, threshold: Severity) -> bool {
+        self.violations
+            .iter()
+            .any(|violation| violation.enforced && violation.severity.at_least(threshold))
+    }
+}
+
+/// The outcome of one contract rule, reported whether or not it passed.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CheckSummary {
+    pub rule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    pub passed: bool,
+    /// Number of rows (or objects) the rule was evaluated against.
+    pub evaluated: usize,
+    pub violations: usize,
+    /// Rows with at least one violation of the rule.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rows: Vec<usize>,
+    #[serde(skip_serializing_if = "is_enforced")]
+    pub enforced: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct RowSummary {
+    pub row: usize,
+    pub status: VerdictStatus,
+    pub violations: usize,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct GroupSummary {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub severity: Severity,
+    pub mode: GroupMode,
+    pub status: VerdictStatus,
+    pub violations: usize,
 }
 
 #[derive(Debug)]
@@ -42,6 +187,9 @@ pub enum RunError {
     Io(io::Error),
     InvalidContract(serde_json::Error),
     InvalidContractRegex(regex::Error),
+    InvalidContractRule(String),
+    /// `--contract-name` names no contract of the file.
+    ContractNotFound(String),
     InvalidOutput(serde_json::Error),
 }
 
@@ -51,6 +199,8 @@ impl fmt::Display for RunError {
             RunError::Io(err) => write!(f, "I/O error: {err}"),
             RunError::InvalidContract(err) => write!(f, "Invalid contract JSON: {err}"),
             RunError::InvalidContractRegex(err) => write!(f, "Invalid contract regex: {err}"),
+            RunError::InvalidContractRule(detail) => write!(f, "Invalid contract rule: {detail}"),
+            RunError::ContractNotFound(detail) => write!(f, "Contract not found: {detail}"),
             RunError::InvalidOutput(err) => write!(f, "Invalid output JSON: {err}"),
         }
     }
@@ -62,24 +212,610 @@ impl Error for RunError {
             RunError::Io(err) => Some(err),
             RunError::InvalidContract(err) => Some(err),
             RunError::InvalidContractRegex(err) => Some(err),
+            RunError::InvalidContractRule(_) | RunError::ContractNotFound(_) => None,
             RunError::InvalidOutput(err) => Some(err),
         }
     }
 }
 
-pub fn run(contract_path: &Path, output_path: &Path) -> Result<Verdict, RunError> {
-    let contract_contents = fs::read_to_string(contract_path).map_err(RunError::Io)?;
-    let output_contents = fs::read_to_string(output_path).map_err(RunError::Io)?;
+/// Run-time overrides of contract settings, e.g. from the command line.
+#[derive(Debug, Clone, Default)]
+pub struct VerifyOptions {
+    /// Overrides the contract's `row_pass_threshold`.
+    pub row_pass_threshold: Option<f64>,
+    /// Overrides the contract's `row_fail_threshold`.
+    pub row_fail_threshold: Option<f64>,
+    /// Attach an excerpt of the offending row to each violation.
+    pub excerpts: Option<ExcerptOptions>,
+    /// Fields whose values are replaced with `[REDACTED]`, at any depth: in
+    /// excerpts, in the `actual`, `expected` and message of violations about
+    /// them, and wherever violations about other fields quote them.
+    pub redact: Vec<String>,
+    /// Overrides the contract's message `locale`.
+    pub locale: Option<String>,
+    /// Turns on strict mode regardless of the contract's `strict`.
+    pub strict: bool,
+    /// Selects a contract of a contract pack; for a plain contract file it
+    /// must match the contract's name.
+    pub contract_name: Option<String>,
+    /// Extracts the JSON from surrounding text regardless of the contract's
+    /// `output_format`.
+    pub extract: bool,
+    /// Merges violations of a rule that differ only in their row, like
+    /// [`CompiledContract::with_dedupe`].
+    pub dedupe: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExcerptOptions {
+    /// Excerpts longer than this many characters are truncated.
+    pub max_len: usize,
+}
+
+const REDACTED: &str = "[REDACTED]";
+
+pub fn run(
+    contract_path: &Path,
+    output_path: &Path,
+    options: &VerifyOptions,
+) -> Result<Verdict, RunError> {
+    let contract = load_contract(contract_path, options)?;
+    let source = read_output(output_path)?;
+    let (output, extraction) = parse_output(&contract, &source)?;
+    let mut verdict = verify_compiled(
+        &contract,
+        &compile_regexes(&contract),
+        &[("output", &output)],
+        options.dedupe,
+    )
+    .pop()
+    .expect("one verdict per output");
+    verdict.extraction = extraction;
+    attach_positions(&mut verdict, &source);
+    if let Some(excerpts) = &options.excerpts {
+        attach_excerpts(&mut verdict, &output, excerpts, &options.redact);
+    }
+    redact_violations(&mut verdict, &options.redact);
+    Ok(verdict)
+}
+
+// Merges the row violations of one rule that are identical except for their
+// row into the first of them, which lists every affected row in `rows`.
+// Systemic failures then read as one finding rather than one per row. The
+// message counts as identical once its `Row <n> ` prefix is dropped, so rows
+// failing a rule in different ways stay apart. Violations are paired with the
+// index of their rule, `None` for those outside any rule.
+fn dedupe_violations(
+    violations: Vec<(Violation, Option<usize>)>,
+) -> Vec<(Violation, Option<usize>)> {
+    let mut merged: Vec<(Violation, Option<usize>)> = Vec::with_capacity(violations.len());
+    let mut index: HashMap<(Option<usize>, String), usize> = HashMap::new();
+    for (violation, rule_idx) in violations {
+        let Some(row) = violation.row else {
+            merged.push((violation, rule_idx));
+            continue;
+        };
+        let prefix = format!("Row {row} ");
+        let key = serde_json::to_string(&Violation {
+            detail: violation
+                .detail
+                .strip_prefix(&prefix)
+                .unwrap_or(&violation.detail)
+                .to_string(),
+            row: None,
+            ..violation.clone()
+        })
+        .expect("serialize violation");
+        match index.get(&(rule_idx, key.clone())) {
+            Some(&idx) => merged[idx].0.rows.push(row),
+            None => {
+                index.insert((rule_idx, key), merged.len());
+                merged.push((
+                    Violation {
+                        rows: vec![row],
+                        ..violation
+                    },
+                    rule_idx,
+                ));
+            }
+        }
+    }
+    for (violation, _) in &mut merged {
+        match violation.rows.len() {
+            // Nothing merged: keep the single row as-is.
+            1 => violation.rows.clear(),
+            0 => {}
+            count => {
+                violation.rows.sort_unstable();
+                if let Some(row) = violation.row.take() {
+                    violation.detail = merged_message(&violation.detail, row, count);
+                }
+            }
+        }
+    }
+    merged
+}
+
+// `Row 0 field 'status' has a disallowed value.` becomes `Field 'status' has
+// a disallowed value in 2 rows.` and `Row 0 is empty.` becomes `2 rows are
+// empty.`; messages of other shapes get the count appended. Catalogs render
+// merged violations with their own `rows_prefix`.
+fn merged_message(detail: &str, row: usize, count: usize) -> String {
+    let sentence = detail.strip_suffix('.').unwrap_or(detail);
+    let Some(rest) = sentence.strip_prefix(&format!("Row {row} ")) else {
+        return format!("{sentence} (in {count} rows).");
+    };
+    if let Some(rest) = rest.strip_prefix("field ") {
+        return format!("Field {rest} in {count} rows.");
+    }
+    let plural = [
+        ("is ", "are "),
+        ("has ", "have "),
+        ("does ", "do "),
+        ("matches ", "match "),
+        ("must ", "must "),
+    ]
+    .into_iter()
+    .find_map(|(singular, plural)| {
+        rest.strip_prefix(singular)
+            .map(|rest| format!("{plural}{rest}"))
+    });
+    match plural {
+        Some(rest) => format!("{count} rows {rest}."),
+        None => format!("{count} rows: {rest}."),
+    }
+}
 
-    let contract: Contract =
-        serde_json::from_str(&contract_contents).map_err(RunError::InvalidContract)?;
-    let output: Value = serde_json::from_str(&output_contents).map_err(RunError::InvalidOutput)?;
+/// Attaches the byte offsets and lines/columns in `source` where the value
+/// each violation points at starts and ends: the row's `field` if present,
+/// else the row, else the whole output. Merged violations get none. Positions
+/// of extracted JSON count from the start of the text it was extracted from.
+pub fn attach_positions(verdict: &mut Verdict, source: &str) {
+    let start = verdict
+        .extraction
+        .as_ref()
+        .map_or(0, |extraction| extraction.position.offset);
+    let Some(spans) = SpanIndex::scan_from(source, start) else {
+        return;
+    };
+    // A merged violation stands for several values.
+    for violation in verdict
+        .violations
+        .iter_mut()
+        .filter(|violation| violation.rows.is_empty())
+    {
+        let row_path: Vec<PathSegment> =
+            violation.row.map(PathSegment::Index).into_iter().collect();
+        let mut field_path = row_path.clone();
+        if let Some(field) = &violation.field {
+            field_path.push(PathSegment::Key(field.clone()));
+            // A key spelled like the path wins, as it did when the rule ran.
+            if let Some(segments) = path::segments(field) {
+                if !spans.contains(&field_path) {
+                    field_path.truncate(row_path.len());
+                    field_path.extend(segments);
+                }
+            }
+        }
+        let path = if spans.contains(&field_path) {
+            field_path
+        } else {
+            row_path
+        };
+        violation.position = spans.position(&path);
+        violation.end_position = spans.end_position(&path);
+    }
+}
+
+/// Attaches a compact, redacted and truncated JSON excerpt of the offending
+/// row (or object output) to each violation; a merged violation gets its
+/// first row's. Violations about an array output as a whole get none.
+pub fn attach_excerpts(
+    verdict: &mut Verdict,
+    output: &Value,
+    options: &ExcerptOptions,
+    redact: &[String],
+) {
+    for violation in &mut verdict.violations {
+        let row = violation.row.or(violation.rows.first().copied());
+        let source = match (row, output) {
+            (Some(row), Value::Array(rows)) => rows.get(row),
+            (None, Value::Object(_)) => Some(output),
+            _ => None,
+        };
+        violation.excerpt = source.map(|value| excerpt(value, options, redact));
+    }
+}
+
+/// Replaces the `actual` and `expected` values of violations about a field
+/// named in `fields` (the last key of a path) with `[REDACTED]`, along with
+/// their text in the message. Values of such fields that a violation about
+/// another field quotes, e.g. the other side of a `field_comparison`, are
+/// redacted too.
+pub fn redact_violations(verdict: &mut Verdict, fields: &[String]) {
+    if fields.is_empty() {
+        return;
+    }
+    let redacted = |field: &str| {
+        let name = match path::segments(field).and_then(|segments| segments.last().cloned()) {
+            Some(PathSegment::Key(key)) => key,
+            _ => field.to_string(),
+        };
+        fields.contains(&name)
+    };
+    for violation in &mut verdict.violations {
+        if let Some(field) = violation.field.clone().filter(|field| redacted(field)) {
+            for value in [&mut violation.actual, &mut violation.expected]
+                .into_iter()
+                .flatten()
+            {
+                violation.detail = redact_value_text(&violation.detail, &field, value);
+                *value = Value::String(REDACTED.to_string());
+            }
+        }
+        for (field, quoted) in &violation.quoted {
+            if !redacted(field) {
+                continue;
+            }
+            violation.detail = redact_value_text(&violation.detail, field, quoted);
+            // Those of combinators count groups rather than hold values.
+            if violation.field.is_none() {
+                continue;
+            }
+            for value in [&mut violation.actual, &mut violation.expected]
+                .into_iter()
+                .flatten()
+            {
+                redact_equal_values(value, quoted);
+            }
+        }
+    }
+}
+
+// Localized messages render values as JSON, built-in ones as plain text.
+fn redact_value_text(message: &str, field: &str, value: &Value) -> String {
+    let mut texts = vec![value.to_string()];
+    match value {
+        Value::String(text) if !text.is_empty() => texts.push(text.clone()),
+        Value::Number(number) => texts.extend(number.as_f64().map(|n| n.to_string())),
+        _ => {}
+    }
+    texts.iter().fold(message.to_string(), |message, text| {
+        redact_text(&message, field, text)
+    })
+}
+
+// Replaces `quoted` wherever it appears in `value`, e.g. as one coordinate of
+// a pair; numbers compare by value.
+fn redact_equal_values(value: &mut Value, quoted: &Value) {
+    let equal = value == quoted || (value.is_number() && value.as_f64() == quoted.as_f64());
+    match value {
+        _ if equal => *value = Value::String(REDACTED.to_string()),
+        Value::Array(items) => {
+            for item in items {
+                redact_equal_values(item, quoted);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Replaces whole-word occurrences of `value` in `message`, after the mention
+// of `field` so the row number and field name of the location stay intact.
+fn redact_text(message: &str, field: &str, value: &str) -> String {
+    let quoted = format!("'{field}'");
+    let from = message.find(&quoted).map_or(0, |idx| idx + quoted.len());
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    // A decimal point inside a number, not a full stop.
+    let decimal = |text: &str| {
+        text.strip_prefix('.')
+            .is_some_and(|text| text.starts_with(|c: char| c.is_ascii_digit()))
+    };
+    let mut redacted = message[..from].to_string();
+    let mut rest = &message[from..];
+    while let Some(idx) = rest.find(value) {
+        let end = idx + value.len();
+        let before = &rest[..idx];
+        let bounded = !before.ends_with(is_word)
+            && !before
+                .strip_suffix('.')
+                .is_some_and(|text| text.ends_with(is_word))
+            && !rest[end..].starts_with(is_word)
+            && !decimal(&rest[end..]);
+        redacted.push_str(&rest[..idx]);
+        redacted.push_str(if bounded { REDACTED } else { &rest[idx..end] });
+        rest = &rest[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+fn excerpt(value: &Value, options: &ExcerptOptions, fields: &[String]) -> String {
+    let mut value = value.clone();
+    redact(&mut value, fields);
+    let text = value.to_string();
+    match text.char_indices().nth(options.max_len) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text,
+    }
+}
+
+fn redact(value: &mut Value, fields: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if fields.contains(key) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact(value, fields);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                redact(item, fields);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Parses a contract file, applies `options`, loads the value files it
+/// references and validates it.
+pub fn load_contract(contract_path: &Path, options: &VerifyOptions) -> Result<Contract, RunError> {
+    let contract_contents = fs::read_to_string(contract_path).map_err(RunError::Io)?;
+    let mut contract = parse_contract(&contract_contents, options.contract_name.as_deref())?;
+    if let Some(threshold) = options.row_pass_threshold {
+        contract.row_pass_threshold = Some(threshold);
+    }
+    if let Some(threshold) = options.row_fail_threshold {
+        contract.row_fail_threshold = Some(threshold);
+    }
+    if let Some(locale) = &options.locale {
+        contract.locale = Some(locale.clone());
+    }
+    contract.strict |= options.strict;
+    if options.extract {
+        contract.output_format = OutputFormat::TextWithJson;
+    }
+    let contract_dir = contract_path.parent().unwrap_or_else(|| Path::new(""));
+    load_values_files(&mut contract, contract_dir)?;
     validate_contract(&contract)?;
+    Ok(contract)
+}
+
+// A contract file holds one contract or, with a top-level `contracts` object,
+// a pack of named contracts.
+pub(crate) fn parse_contract(contents: &str, name: Option<&str>) -> Result<Contract, RunError> {
+    let document: Value = serde_json::from_str(contents).map_err(RunError::InvalidContract)?;
+    if document.get("contracts").is_none() {
+        let contract: Contract =
+            serde_json::from_str(contents).map_err(RunError::InvalidContract)?;
+        return match name {
+            Some(name) if contract.contract.as_deref() != Some(name) => {
+                let held = match &contract.contract {
+                    Some(held) => format!("a single contract named '{held}'"),
+                    None => "a single unnamed contract".to_string(),
+                };
+                Err(RunError::ContractNotFound(format!(
+                    "the contract file holds {held}, not '{name}'."
+                )))
+            }
+            _ => Ok(contract),
+        };
+    }
+    let pack: ContractPack = serde_json::from_str(contents).map_err(RunError::InvalidContract)?;
+    let available = pack
+        .contracts
+        .keys()
+        .map(|name| format!("'{name}'"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let Some(name) = name else {
+        return Err(RunError::ContractNotFound(format!(
+            "the file is a contract pack; select one of {available} with --contract-name."
+        )));
+    };
+    pack.select(name).ok_or_else(|| {
+        RunError::ContractNotFound(format!(
+            "the contract pack has no contract '{name}'; available: {available}."
+        ))
+    })
+}
+
+/// Reads and parses an output file, or standard input for `-`, returning its
+/// text alongside the value so positions can be resolved against it.
+pub fn load_output(output_path: &Path) -> Result<(String, Value), RunError> {
+    let output_contents = read_output(output_path)?;
+    let output = serde_json::from_str(&output_contents).map_err(RunError::InvalidOutput)?;
+    Ok((output_contents, output))
+}
+
+/// Reads an output file, or standard input for `-`.
+pub fn read_output(output_path: &Path) -> Result<String, RunError> {
+    if output_path == Path::new("-") {
+        let mut contents = String::new();
+        io::stdin()
+            .read_to_string(&mut contents)
+            .map_err(RunError::Io)?;
+        Ok(contents)
+    } else {
+        fs::read_to_string(output_path).map_err(RunError::Io)
+    }
+}
+
+/// Parses output text as the contract's `output_format` says. Text that is
+/// not JSON as a whole has its JSON extracted for `text_with_json`, which
+/// the returned [`Extraction`] records.
+pub fn parse_output(
+    contract: &Contract,
+    text: &str,
+) -> Result<(Value, Option<Extraction>), RunError> {
+    match serde_json::from_str(text) {
+        Ok(output) => Ok((output, None)),
+        Err(err) if contract.output_format == OutputFormat::TextWithJson => extract_json(text)
+            .map(|(output, extraction)| (output, Some(extraction)))
+            .ok_or(RunError::InvalidOutput(err)),
+        Err(err) => Err(RunError::InvalidOutput(err)),
+    }
+}
+
+/// Validates `contract` and verifies `output` against it. A contract built
+/// or deserialized directly has not been validated yet, so an invalid one,
+/// such as one with a bad regex, is an error. Use [`CompiledContract`] to
+/// validate once for many outputs.
+pub fn verify(contract: &Contract, output: &Value) -> Result<Verdict, RunError> {
+    Ok(verify_batch(contract, &[("output", output)])?
+        .pop()
+        .expect("one verdict per output"))
+}
+
+/// Verifies several labelled outputs against one contract, returning one
+/// verdict per output in order. Outputs are checked in parallel. Rules
+/// spanning outputs (such as `unique_across_outputs`) see all of them; the
+/// label names the output in their messages. Validates `contract` first,
+/// like [`verify`].
+pub fn verify_batch(
+    contract: &Contract,
+    outputs: &[(&str, &Value)],
+) -> Result<Vec<Verdict>, RunError> {
+    validate_contract(contract)?;
+    Ok(verify_compiled(
+        contract,
+        &compile_regexes(contract),
+        outputs,
+        false,
+    ))
+}
+
+/// A validated contract with its regex patterns compiled once, for callers
+/// that verify many outputs against the same contract.
+#[derive(Debug, Clone)]
+pub struct CompiledContract {
+    contract: Contract,
+    regexes: HashMap<String, Regex>,
+    dedupe: bool,
+}
+
+impl CompiledContract {
+    /// Validates `contract` and compiles its patterns.
+    pub fn new(contract: Contract) -> Result<Self, RunError> {
+        validate_contract(&contract)?;
+        let regexes = compile_regexes(&contract);
+        Ok(CompiledContract {
+            contract,
+            regexes,
+            dedupe: false,
+        })
+    }
+
+    /// Merges the row violations of a rule that differ only in their row into
+    /// one listing the affected `rows`. The merged entry names no row and
+    /// gets no position; its excerpt is the first row's.
+    pub fn with_dedupe(self, dedupe: bool) -> Self {
+        CompiledContract { dedupe, ..self }
+    }
+
+    pub fn contract(&self) -> &Contract {
+        &self.contract
+    }
+
+    pub fn verify(&self, output: &Value) -> Verdict {
+        self.verify_batch(&[("output", output)])
+            .pop()
+            .expect("one verdict per output")
+    }
+
+    /// Like [`verify_batch`], without recompiling patterns.
+    pub fn verify_batch(&self, outputs: &[(&str, &Value)]) -> Vec<Verdict> {
+        verify_compiled(&self.contract, &self.regexes, outputs, self.dedupe)
+    }
+}
+
+// The compiled pattern of every `regex` rule, nested ones included, keyed by
+// pattern. Invalid patterns are left out; validation rejects them.
+fn compile_regexes(contract: &Contract) -> HashMap<String, Regex> {
+    fn collect<'c>(contract: &'c Contract, rule: &'c Rule, patterns: &mut Vec<&'c str>) {
+        match rule {
+            Rule::Regex {
+                pattern,
+                pattern_ref,
+                ..
+            } => patterns
+                .extend(contract.resolve_pattern(pattern.as_deref(), pattern_ref.as_deref())),
+            Rule::AnyOf { groups } | Rule::OneOf { groups } => {
+                for rule in groups.iter().flatten() {
+                    collect(contract, rule, patterns);
+                }
+            }
+            Rule::Not { rules } => {
+                for rule in rules {
+                    collect(contract, rule, patterns);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut patterns = Vec::new();
+    for (_, spec) in contract.rule_specs() {
+        collect(contract, &spec.rule, &mut patterns);
+    }
+    patterns
+        .into_iter()
+        .filter_map(|pattern| Some((pattern.to_string(), Regex::new(pattern).ok()?)))
+        .collect()
+}
+
+fn verify_compiled(
+    contract: &Contract,
+    regexes: &HashMap<String, Regex>,
+    outputs: &[(&str, &Value)],
+    dedupe: bool,
+) -> Vec<Verdict> {
+    let ctx = EvalContext { contract, regexes };
+    let specs: Vec<(Option<&RuleGroup>, &RuleSpec)> = contract.rule_specs().collect();
 
-    Ok(verify(&contract, &output))
+    let mut per_output: Vec<Vec<Vec<Violation>>> = outputs
+        .par_iter()
+        .map(|(_, output)| {
+            specs
+                .iter()
+                .map(|(_, spec)| {
+                    let mut rule_violations = Vec::new();
+                    if !spans_outputs(&spec.rule) {
+                        check_rule(&ctx, &spec.rule, output, &mut rule_violations);
+                    }
+                    rule_violations
+                })
+                .collect()
+        })
+        .collect();
+
+    for (rule_idx, (_, spec)) in specs.iter().enumerate() {
+        if spans_outputs(&spec.rule) {
+            let results = check_across_outputs(&spec.rule, outputs);
+            for (output_violations, rule_violations) in per_output.iter_mut().zip(results) {
+                output_violations[rule_idx] = rule_violations;
+            }
+        }
+    }
+
+    outputs
+        .par_iter()
+        .zip(per_output)
+        .map(|((_, output), rule_violations)| {
+            build_verdict(contract, &specs, output, rule_violations, dedupe)
+        })
+        .collect()
 }
 
-pub fn verify(contract: &Contract, output: &Value) -> Verdict {
+fn build_verdict(
+    contract: &Contract,
+    specs: &[(Option<&RuleGroup>, &RuleSpec)],
+    output: &Value,
+    rule_violations: Vec<Vec<Violation>>,
+    dedupe: bool,
+) -> Verdict {
     let mut violations = Vec::new();
 
     match contract.output_type {
@@ -93,342 +829,2763 @@ pub fn verify(contract: &Contract, output: &Value) -> Verdict {
         )),
         _ => {}
     }
+    if contract.strict {
+        check_unknown_fields(contract, output, &mut violations);
+    }
+    let structural_violations = violations.len();
 
-    for rule in &contract.rules {
-        check_rule(rule, output, &mut violations);
+    let mut outcomes: Vec<RuleOutcome> = specs
+        .iter()
+        .zip(rule_violations)
+        .map(|((group, spec), mut rule_violations)| {
+            let hint = (!rule_violations.is_empty()).then(|| {
+                spec.hint
+                    .clone()
+                    .unwrap_or_else(|| rule_hint(contract, &spec.rule))
+            });
+            let example = if rule_violations.is_empty() {
+                None
+            } else {
+                rule_example(contract, &spec.rule)
+            };
+            let severity = spec
+                .severity
+                .or(group.map(|group| group.severity))
+                .unwrap_or_default();
+            for violation in &mut rule_violations {
+                violation.group = group.map(|group| group.name.clone());
+                violation.enforced = spec.enforce;
+                violation.severity = severity;
+                // Shape problems are not fixed by following the rule's hint.
+                if violation.code != codes::UNSUITABLE_SHAPE {
+                    violation.hint.clone_from(&hint);
+                    violation.example.clone_from(&example);
+                }
+            }
+            RuleOutcome {
+                group: *group,
+                spec,
+                weight: spec.weight.unwrap_or(DEFAULT_RULE_WEIGHT),
+                enforced: spec.enforce,
+                violations: rule_violations,
+            }
+        })
+        .collect();
+
+    let groups = summarize_groups(contract, &mut outcomes);
+
+    let mut total_weight = 0.0;
+    let mut passed_weight = 0.0;
+    let mut checks = Vec::with_capacity(outcomes.len());
+    let mut rule_indices: Vec<Option<usize>> = vec![None; violations.len()];
+    for (rule_idx, outcome) in outcomes.into_iter().enumerate() {
+        // Warn-only rules are reported but do not count towards the score.
+        if outcome.enforced {
+            total_weight += outcome.weight;
+            if outcome.violations.is_empty() {
+                passed_weight += outcome.weight;
+            }
+        }
+        checks.push(summarize_check(&outcome, output));
+        rule_indices.extend(std::iter::repeat_n(
+            Some(rule_idx),
+            outcome.violations.len(),
+        ));
+        violations.extend(outcome.violations);
+    }
+
+    let mut verdict = Verdict {
+        status: VerdictStatus::Pass,
+        violations,
+        score: None,
+        pass_threshold: None,
+        groups,
+        checks,
+        rows: Vec::new(),
+        row_pass_rate: None,
+        row_pass_threshold: None,
+        row_fail_threshold: None,
+        extraction: None,
+    };
+    verdict.rows = summarize_rows(output, &verdict.violations);
+
+    if let Some(threshold) = contract.row_pass_threshold {
+        apply_row_threshold(&mut verdict, threshold, contract.row_fail_threshold);
+    } else if let Some(scoring) = &contract.scoring {
+        let score = if total_weight > 0.0 {
+            passed_weight / total_weight
+        } else {
+            1.0
+        };
+        // An output of the wrong shape fails regardless of how the rules scored.
+        if structural_violations > 0 || score < scoring.pass_threshold {
+            verdict.status = VerdictStatus::Fail;
+        }
+        verdict.score = Some(score);
+        verdict.pass_threshold = Some(scoring.pass_threshold);
+    } else if verdict
+        .violations
+        .iter()
+        .any(|violation| violation.enforced)
+    {
+        verdict.status = VerdictStatus::Fail;
     }
 
-    let status = if violations.is_empty() {
-        VerdictStatus::Pass
+    // Merged and localized last, once rows and status have been worked out
+    // from the violations of every row.
+    let mut violations: Vec<(Violation, Option<usize>)> =
+        verdict.violations.drain(..).zip(rule_indices).collect();
+    if dedupe {
+        violations = dedupe_violations(violations);
+    }
+    let messages = Messages::for_contract(contract);
+    verdict.violations = violations
+        .into_iter()
+        .map(|(mut violation, rule_idx)| {
+            if let Some(messages) = &messages {
+                let field = rule_idx.and_then(|idx| specs[idx].1.rule.field());
+                messages.localize(&mut violation, field);
+            }
+            violation
+        })
+        .collect();
+    verdict
+}
+
+// Row mode: each row passes or fails on its own enforced violations and the
+// output passes when enough rows do, or is partial when at least
+// `fail_threshold` of them do. Violations not tied to a row (wrong output
+// shape, `min_items`, ...) still fail the whole output.
+fn apply_row_threshold(verdict: &mut Verdict, threshold: f64, fail_threshold: Option<f64>) {
+    let row_count = verdict.rows.len();
+    let output_failed = verdict
+        .violations
+        .iter()
+        .any(|violation| violation.enforced && violation.row.is_none());
+    let passed = verdict
+        .rows
+        .iter()
+        .filter(|summary| summary.status == VerdictStatus::Pass)
+        .count();
+    let pass_rate = if row_count > 0 {
+        passed as f64 / row_count as f64
+    } else {
+        1.0
+    };
+    verdict.status = if output_failed {
+        VerdictStatus::Fail
+    } else if pass_rate >= threshold {
+        if passed < row_count {
+            VerdictStatus::PartialPass
+        } else {
+            VerdictStatus::Pass
+        }
+    } else if fail_threshold.is_some_and(|fail_threshold| pass_rate >= fail_threshold) {
+        VerdictStatus::Partial
     } else {
         VerdictStatus::Fail
     };
+    verdict.row_pass_rate = Some(pass_rate);
+    verdict.row_pass_threshold = Some(threshold);
+    verdict.row_fail_threshold = fail_threshold;
+}
+
+// One summary per row of an array output; a row fails on its own enforced
+// violations.
+fn summarize_rows(output: &Value, violations: &[Violation]) -> Vec<RowSummary> {
+    let row_count = output.as_array().map_or(0, Vec::len);
+    let mut rows: Vec<RowSummary> = (0..row_count)
+        .map(|row| RowSummary {
+            row,
+            status: VerdictStatus::Pass,
+            violations: 0,
+        })
+        .collect();
+    for violation in violations {
+        if let Some(summary) = violation.row.and_then(|row| rows.get_mut(row)) {
+            summary.violations += 1;
+            if violation.enforced {
+                summary.status = VerdictStatus::Fail;
+            }
+        }
+    }
+    rows
+}
+
+fn summarize_check(outcome: &RuleOutcome, output: &Value) -> CheckSummary {
+    let rule = &outcome.spec.rule;
+    // Output-level rules are evaluated once; all others once per row.
+    let evaluated = match (rule, output) {
+        (
+            Rule::MinItems { .. }
+            | Rule::MaxItems { .. }
+            | Rule::CoversValues { .. }
+            | Rule::UniqueValues { .. },
+            _,
+        ) => 1,
+        (_, Value::Array(rows)) => rows.len(),
+        _ => 1,
+    };
+    let mut rows: Vec<usize> = outcome.violations.iter().filter_map(|v| v.row).collect();
+    rows.dedup();
+    CheckSummary {
+        rule: rule.name().to_string(),
+        field: rule.field().map(str::to_string),
+        group: outcome.group.map(|group| group.name.clone()),
+        passed: outcome.violations.is_empty(),
+        evaluated,
+        violations: outcome.violations.len(),
+        rows,
+        enforced: outcome.enforced,
+    }
+}
+
+// Contract-wide state shared by the rule checks, including rules nested in combinators.
+struct EvalContext<'a> {
+    contract: &'a Contract,
+    regexes: &'a HashMap<String, Regex>,
+}
 
-    Verdict { status, violations }
+impl EvalContext<'_> {
+    // The resolved pattern and its compiled regex, compiled on the spot only
+    // if it was not compiled up front.
+    fn regex<'r>(
+        &'r self,
+        pattern: &'r Option<String>,
+        pattern_ref: &Option<String>,
+    ) -> (&'r str, Cow<'r, Regex>) {
+        let pattern = self
+            .contract
+            .resolve_pattern(pattern.as_deref(), pattern_ref.as_deref())
+            .expect("regex patterns validated in run()");
+        let regex = match self.regexes.get(pattern) {
+            Some(regex) => Cow::Borrowed(regex),
+            None => Cow::Owned(Regex::new(pattern).expect("regex patterns validated in run()")),
+        };
+        (pattern, regex)
+    }
+}
+
+struct RuleOutcome<'a> {
+    group: Option<&'a RuleGroup>,
+    spec: &'a RuleSpec,
+    weight: f64,
+    enforced: bool,
+    violations: Vec<Violation>,
+}
+
+// Applies each group's all/any semantics to the outcomes of its rules and
+// returns the per-group summaries. A passing `any` group drops the violations
+// of its failing rules.
+fn summarize_groups(contract: &Contract, outcomes: &mut [RuleOutcome]) -> Vec<GroupSummary> {
+    contract
+        .groups
+        .iter()
+        .map(|group| {
+            let mut members: Vec<&mut RuleOutcome> = outcomes
+                .iter_mut()
+                .filter(|outcome| outcome.group.is_some_and(|g| std::ptr::eq(g, group)))
+                .collect();
+            if group.mode == GroupMode::Any
+                && members.iter().any(|outcome| outcome.violations.is_empty())
+            {
+                for outcome in &mut members {
+                    outcome.violations.clear();
+                }
+            }
+            let violations: usize = members.iter().map(|outcome| outcome.violations.len()).sum();
+            let failed = members
+                .iter()
+                .any(|outcome| outcome.enforced && !outcome.violations.is_empty());
+            GroupSummary {
+                name: group.name.clone(),
+                description: group.description.clone(),
+                severity: group.severity,
+                mode: group.mode,
+                status: if !failed {
+                    VerdictStatus::Pass
+                } else {
+                    VerdictStatus::Fail
+                },
+                violations,
+            }
+        })
+        .collect()
+}
+
+impl Violation {
+    pub fn new(rule_name: &str, detail: String) -> Self {
+        Violation {
+            rule_name: rule_name.to_string(),
+            detail,
+            code: codes::default_code(rule_name),
+            field: None,
+            rule: None,
+            expected: None,
+            actual: None,
+            suggestion: None,
+            hint: None,
+            group: None,
+            example: None,
+            excerpt: None,
+            position: None,
+            end_position: None,
+            rows: Vec::new(),
+            row: None,
+            severity: Severity::Error,
+            enforced: true,
+            quoted: Vec::new(),
+        }
+    }
+}
+
+impl Violation {
+    fn with_code(self, code: &'static str) -> Self {
+        Violation { code, ..self }
+    }
 }
 
 fn simple_violation(rule_name: &str, detail: String) -> Violation {
-    Violation {
-        rule_name: rule_name.to_string(),
-        detail,
-        field: None,
-        rule: None,
-        expected: None,
-        actual: None,
+    Violation::new(rule_name, detail)
+}
+
+fn shape_violation(rule_name: &str, detail: String) -> Violation {
+    simple_violation(rule_name, detail).with_code(codes::UNSUITABLE_SHAPE)
+}
+
+// Without a `default` this stays the bare violation the rule always reported.
+fn required_field_violation(field: &str, default: Option<&Value>, detail: String) -> Violation {
+    let violation = Violation {
+        field: Some(field.to_string()),
+        ..simple_violation("RequiredField", detail)
+    };
+    match default {
+        Some(default) => Violation {
+            expected: Some(default.clone()),
+            suggestion: Some(format!("Set '{field}' to {default}.")),
+            ..violation
+        },
+        None => violation,
     }
 }
 
 fn allowed_values_violation(
     field: &str,
-    expected: &[Value],
+    expected: &Value,
     actual: &Value,
     detail: String,
 ) -> Violation {
     Violation {
-        rule_name: "AllowedValues".to_string(),
-        detail,
         field: Some(field.to_string()),
         rule: Some("allowed_values".to_string()),
-        expected: Some(Value::Array(expected.to_vec())),
+        expected: Some(expected.clone()),
+        actual: Some(actual.clone()),
+        ..simple_violation("AllowedValues", detail)
+    }
+}
+
+fn const_violation(field: &str, expected: &Value, actual: &Value, detail: String) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        rule: Some("const".to_string()),
+        expected: Some(expected.clone()),
+        actual: Some(actual.clone()),
+        ..simple_violation("Const", detail)
+    }
+}
+
+fn unique_violation(
+    rule_name: &str,
+    rule: &str,
+    field: &str,
+    actual: &Value,
+    detail: String,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        rule: Some(rule.to_string()),
+        actual: Some(actual.clone()),
+        ..simple_violation(rule_name, detail)
+    }
+}
+
+fn consistent_violation(
+    field: &str,
+    expected: &Value,
+    actual: &Value,
+    detail: String,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        rule: Some("consistent_across_outputs".to_string()),
+        expected: Some(expected.clone()),
         actual: Some(actual.clone()),
+        ..simple_violation("ConsistentAcrossOutputs", detail)
     }
 }
 
 fn regex_violation(field: &str, pattern: &str, actual: &Value, detail: String) -> Violation {
     Violation {
-        rule_name: "Regex".to_string(),
-        detail,
         field: Some(field.to_string()),
         rule: Some("regex".to_string()),
         expected: Some(Value::String(pattern.to_string())),
         actual: Some(actual.clone()),
+        ..simple_violation("Regex", detail)
     }
 }
 
 fn min_items_violation(value: u64, actual: Value, detail: String) -> Violation {
     Violation {
-        rule_name: "MinItems".to_string(),
-        detail,
         field: Some("$".to_string()),
         rule: Some("min_items".to_string()),
         expected: Some(Value::from(value)),
         actual: Some(actual),
+        ..simple_violation("MinItems", detail)
     }
 }
 
-fn validate_contract(contract: &Contract) -> Result<(), RunError> {
-    for rule in &contract.rules {
-        if let Rule::Regex { pattern, .. } = rule {
-            Regex::new(pattern).map_err(RunError::InvalidContractRegex)?;
+fn max_items_violation(value: u64, actual: Value, detail: String) -> Violation {
+    Violation {
+        field: Some("$".to_string()),
+        rule: Some("max_items".to_string()),
+        expected: Some(Value::from(value)),
+        actual: Some(actual),
+        ..simple_violation("MaxItems", detail)
+    }
+}
+
+fn number_range_violation(
+    field: &str,
+    expected: &Value,
+    actual: Value,
+    detail: String,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        rule: Some("number_range".to_string()),
+        expected: Some(expected.clone()),
+        actual: Some(actual),
+        ..simple_violation("NumberRange", detail)
+    }
+}
+
+fn string_length_violation(
+    field: &str,
+    expected: &Value,
+    actual: Value,
+    detail: String,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        rule: Some("string_length".to_string()),
+        expected: Some(expected.clone()),
+        actual: Some(actual),
+        ..simple_violation("StringLength", detail)
+    }
+}
+
+fn field_comparison_violation(
+    field: &str,
+    expected: &Value,
+    actual: Value,
+    detail: String,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        rule: Some("field_comparison".to_string()),
+        expected: Some(expected.clone()),
+        actual: Some(actual),
+        ..simple_violation("FieldComparison", detail)
+    }
+}
+
+fn embedded_json_violation(
+    field: &str,
+    expected: &ValueType,
+    actual: &Value,
+    detail: String,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        rule: Some("embedded_json".to_string()),
+        expected: Some(Value::String(value_type_label(expected).to_string())),
+        actual: Some(actual.clone()),
+        ..simple_violation("EmbeddedJson", detail)
+    }
+}
+
+fn check_digit_violation(
+    field: &str,
+    scheme: CheckDigitScheme,
+    actual: &Value,
+    detail: String,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        rule: Some("check_digit".to_string()),
+        expected: Some(Value::String(scheme.name().to_string())),
+        actual: Some(actual.clone()),
+        ..simple_violation("CheckDigit", detail)
+    }
+}
+
+fn geo_coordinate_violation(
+    field: &str,
+    expected: &Value,
+    actual: &Value,
+    detail: String,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        rule: Some("geo_coordinate".to_string()),
+        expected: Some(expected.clone()),
+        actual: Some(actual.clone()),
+        ..simple_violation("GeoCoordinate", detail)
+    }
+}
+
+fn field_array_length_violation(
+    field: &str,
+    expected: &Value,
+    actual: Value,
+    detail: String,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        rule: Some("field_array_length".to_string()),
+        expected: Some(expected.clone()),
+        actual: Some(actual),
+        ..simple_violation("FieldArrayLength", detail)
+    }
+}
+
+fn combinator_violation(combinator: Combinator, passing: usize, detail: String) -> Violation {
+    let expected = match combinator {
+        Combinator::AnyOf => "at least one passing group",
+        Combinator::OneOf => "exactly one passing group",
+        Combinator::Not => "negated rules must not pass",
+    };
+    let violation = Violation {
+        rule: Some(combinator.rule().to_string()),
+        expected: Some(Value::String(expected.to_string())),
+        actual: Some(Value::from(passing)),
+        ..simple_violation(combinator.rule_name(), detail)
+    };
+    match combinator {
+        Combinator::OneOf if passing > 1 => violation.with_code(codes::MULTIPLE_MATCHING_GROUPS),
+        _ => violation,
+    }
+}
+
+fn approx_equal_violation(
+    field: &str,
+    expected: Option<Value>,
+    actual: &Value,
+    detail: String,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        rule: Some("approx_equal".to_string()),
+        expected,
+        actual: Some(actual.clone()),
+        ..simple_violation("ApproxEqual", detail)
+    }
+}
+
+fn datetime_format_violation(
+    field: &str,
+    format: DatetimeFormat,
+    actual: &Value,
+    detail: String,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        rule: Some("datetime_format".to_string()),
+        expected: Some(Value::String(format.label())),
+        actual: Some(actual.clone()),
+        ..simple_violation("DatetimeFormat", detail)
+    }
+}
+
+fn datetime_order_violation(
+    field: &str,
+    operator: ComparisonOperator,
+    (other_field, other): (&str, &Value),
+    actual: &Value,
+    detail: String,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        quoted: vec![(other_field.to_string(), other.clone())],
+        rule: Some("datetime_order".to_string()),
+        expected: Some(Value::String(format!(
+            "{} {other_field}",
+            operator.symbol()
+        ))),
+        actual: Some(actual.clone()),
+        ..simple_violation("DatetimeOrder", detail)
+    }
+}
+
+fn locale_number_violation(
+    field: &str,
+    expected: Value,
+    actual: &Value,
+    detail: String,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        rule: Some("locale_number".to_string()),
+        expected: Some(expected),
+        actual: Some(actual.clone()),
+        ..simple_violation("LocaleNumber", detail)
+    }
+}
+
+pub(crate) fn load_values_files(
+    contract: &mut Contract,
+    contract_dir: &Path,
+) -> Result<(), RunError> {
+    let specs = contract.rules.iter_mut().chain(
+        contract
+            .groups
+            .iter_mut()
+            .flat_map(|group| group.rules.iter_mut()),
+    );
+    for spec in specs {
+        load_rule_values_files(&mut spec.rule, contract_dir)?;
+    }
+    Ok(())
+}
+
+fn load_rule_values_files(rule: &mut Rule, contract_dir: &Path) -> Result<(), RunError> {
+    match rule {
+        Rule::AllowedValues {
+            values,
+            values_file: Some(values_file),
+            values_file_header,
+            ..
+        } => {
+            let path = contract_dir.join(values_file.as_str());
+            let loaded = read_values_file(&path, *values_file_header).map_err(|detail| {
+                RunError::InvalidContractRule(format!(
+                    "Failed to load values_file '{}': {detail}",
+                    path.display()
+                ))
+            })?;
+            values.extend(loaded);
+        }
+        Rule::AnyOf { groups } | Rule::OneOf { groups } => {
+            for rule in groups.iter_mut().flatten() {
+                load_rule_values_files(rule, contract_dir)?;
+            }
+        }
+        Rule::Not { rules } => {
+            for rule in rules {
+                load_rule_values_files(rule, contract_dir)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// `.json` files hold an array of values; `.csv` files contribute the first
+// column of each row; anything else is read as one string value per line.
+fn read_values_file(path: &Path, header: bool) -> Result<Vec<Value>, String> {
+    let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+
+    match extension.as_deref() {
+        Some("json") => match serde_json::from_str(&contents) {
+            Ok(Value::Array(values)) => Ok(values),
+            Ok(_) => Err("expected a JSON array of values".to_string()),
+            Err(err) => Err(err.to_string()),
+        },
+        Some("csv") => Ok(csv_first_column(&contents)?
+            .into_iter()
+            .skip(usize::from(header))
+            .map(Value::String)
+            .collect()),
+        _ => Ok(non_empty_lines(&contents)
+            .map(|line| Value::String(line.to_string()))
+            .collect()),
+    }
+}
+
+// The first field of each non-empty CSV record. Quoted fields may hold
+// commas, line breaks and doubled quotes; unquoted ones are trimmed.
+fn csv_first_column(contents: &str) -> Result<Vec<String>, String> {
+    let mut column = Vec::new();
+    let mut chars = contents.chars().peekable();
+    let mut line = 1;
+    while chars.peek().is_some() {
+        let mut fields: Vec<String> = Vec::new();
+        loop {
+            let mut field = String::new();
+            if chars.peek() == Some(&'"') {
+                let start = line;
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            line += usize::from(c == '\n');
+                            field.push(c);
+                        }
+                        None => return Err(format!("unterminated quoted field on line {start}")),
+                    }
+                }
+                // Anything between the closing quote and the separator.
+                while chars.peek().is_some_and(|c| !matches!(c, ',' | '\n')) {
+                    chars.next();
+                }
+            } else {
+                while let Some(c) = chars.next_if(|c| !matches!(c, ',' | '\n')) {
+                    field.push(c);
+                }
+                field = field.trim().to_string();
+            }
+            fields.push(field);
+            match chars.next() {
+                Some(',') => continue,
+                Some(_) => line += 1,
+                None => {}
+            }
+            break;
+        }
+        if fields.len() > 1 || !fields[0].is_empty() {
+            column.push(fields.swap_remove(0));
+        }
+    }
+    Ok(column)
+}
+
+fn non_empty_lines(contents: &str) -> impl Iterator<Item = &str> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+}
+
+pub(crate) fn validate_contract(contract: &Contract) -> Result<(), RunError> {
+    if let Some(scoring) = &contract.scoring {
+        if !(0.0..=1.0).contains(&scoring.pass_threshold) {
+            return Err(RunError::InvalidContractRule(format!(
+                "'scoring.pass_threshold' must be between 0 and 1, got {}.",
+                scoring.pass_threshold
+            )));
+        }
+    }
+    if let Some(threshold) = contract.row_pass_threshold {
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(RunError::InvalidContractRule(format!(
+                "'row_pass_threshold' must be between 0 and 1, got {threshold}."
+            )));
+        }
+        if contract.scoring.is_some() {
+            return Err(RunError::InvalidContractRule(
+                "'row_pass_threshold' cannot be combined with 'scoring'.".to_string(),
+            ));
+        }
+        if contract.output_type != OutputType::Array {
+            return Err(RunError::InvalidContractRule(
+                "'row_pass_threshold' requires output_type 'array'.".to_string(),
+            ));
+        }
+    }
+    if let Some(fail_threshold) = contract.row_fail_threshold {
+        let Some(pass_threshold) = contract.row_pass_threshold else {
+            return Err(RunError::InvalidContractRule(
+                "'row_fail_threshold' requires 'row_pass_threshold'.".to_string(),
+            ));
+        };
+        if !(0.0..=pass_threshold).contains(&fail_threshold) {
+            return Err(RunError::InvalidContractRule(format!(
+                "'row_fail_threshold' must be between 0 and 'row_pass_threshold' ({pass_threshold}), got {fail_threshold}."
+            )));
+        }
+    }
+    if contract.strict {
+        let mut seen = Vec::new();
+        for (_, spec) in contract.rule_specs() {
+            let rule = serde_json::to_value(&spec.rule).expect("serialize rule");
+            if seen.contains(&rule) {
+                return Err(RunError::InvalidContractRule(format!(
+                    "Duplicate rule {rule} is not allowed in strict mode."
+                )));
+            }
+            seen.push(rule);
+        }
+    }
+    if let Some(locale) = &contract.locale {
+        if !is_known_locale(contract, locale) {
+            return Err(RunError::InvalidContractRule(format!(
+                "Unknown locale '{locale}': no built-in catalog or contract messages."
+            )));
+        }
+    }
+    for (name, pattern) in &contract.patterns {
+        Regex::new(pattern).map_err(RunError::InvalidContractRegex)?;
+        if name.trim().is_empty() {
+            return Err(RunError::InvalidContractRule(
+                "Named patterns require a non-empty name.".to_string(),
+            ));
+        }
+    }
+    let mut group_names = std::collections::BTreeSet::new();
+    for group in &contract.groups {
+        if group.name.trim().is_empty() {
+            return Err(RunError::InvalidContractRule(
+                "Rule groups require a non-empty 'name'.".to_string(),
+            ));
+        }
+        if !group_names.insert(group.name.as_str()) {
+            return Err(RunError::InvalidContractRule(format!(
+                "Duplicate rule group name '{}'.",
+                group.name
+            )));
+        }
+    }
+    for (_, spec) in contract.rule_specs() {
+        if let Some(weight) = spec.weight {
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(RunError::InvalidContractRule(format!(
+                    "Rule weight must be a non-negative number, got {weight}."
+                )));
+            }
+        }
+        validate_rules(contract, std::slice::from_ref(&spec.rule))?;
+    }
+    Ok(())
+}
+
+fn validate_rules(contract: &Contract, rules: &[Rule]) -> Result<(), RunError> {
+    for rule in rules {
+        // `key_order` lists literal keys, not paths.
+        if !matches!(rule, Rule::KeyOrder { .. }) {
+            for field in rule.referenced_fields() {
+                path::validate(field).map_err(|problem| {
+                    RunError::InvalidContractRule(format!(
+                        "Invalid field path in '{}': {problem}. To match a key spelled '{field}' literally, write it as a JSON Pointer: '/{}'.",
+                        rule.name(),
+                        field.replace('~', "~0").replace('/', "~1")
+                    ))
+                })?;
+            }
+        }
+        match rule {
+            Rule::AllowedValues {
+                values,
+                values_file: None,
+                ..
+            } if values.is_empty() => {
+                return Err(RunError::InvalidContractRule(
+                    "'allowed_values' requires 'values' or 'values_file'.".to_string(),
+                ));
+            }
+            Rule::CoversValues { values, .. } if values.is_empty() => {
+                return Err(RunError::InvalidContractRule(
+                    "'covers_values' requires at least one value.".to_string(),
+                ));
+            }
+            Rule::Regex {
+                pattern,
+                pattern_ref,
+                ..
+            } => {
+                if pattern.is_some() == pattern_ref.is_some() {
+                    return Err(RunError::InvalidContractRule(
+                        "'regex' requires exactly one of 'pattern' or 'pattern_ref'.".to_string(),
+                    ));
+                }
+                let resolved = contract
+                    .resolve_pattern(pattern.as_deref(), pattern_ref.as_deref())
+                    .ok_or_else(|| {
+                        RunError::InvalidContractRule(format!(
+                            "Unknown pattern_ref '{}'.",
+                            pattern_ref.as_deref().unwrap_or_default()
+                        ))
+                    })?;
+                Regex::new(resolved).map_err(RunError::InvalidContractRegex)?;
+            }
+            Rule::ApproxEqual {
+                target,
+                other_field,
+                abs_tolerance,
+                rel_tolerance,
+                ..
+            } => {
+                if target.is_some() == other_field.is_some() {
+                    return Err(RunError::InvalidContractRule(
+                        "'approx_equal' requires exactly one of 'target' or 'other_field'."
+                            .to_string(),
+                    ));
+                }
+                let tolerances = [abs_tolerance, rel_tolerance];
+                if tolerances
+                    .into_iter()
+                    .flatten()
+                    .any(|tolerance| !tolerance.is_finite() || *tolerance < 0.0)
+                {
+                    return Err(RunError::InvalidContractRule(
+                        "'approx_equal' tolerances must be non-negative numbers.".to_string(),
+                    ));
+                }
+            }
+            Rule::DatetimeFormat { format, preset, .. } => match (format, preset) {
+                (Some(format), None) => {
+                    datetime::validate_format(format).map_err(RunError::InvalidContractRule)?;
+                }
+                (None, Some(_)) => {}
+                _ => {
+                    return Err(RunError::InvalidContractRule(
+                        "'datetime_format' requires exactly one of 'format' or 'preset'."
+                            .to_string(),
+                    ));
+                }
+            },
+            Rule::DatetimeOrder {
+                format, timezone, ..
+            } => {
+                if let Some(format) = format {
+                    datetime::validate_format(format).map_err(RunError::InvalidContractRule)?;
+                }
+                if let Some(timezone) = timezone {
+                    datetime::parse_offset(timezone).map_err(RunError::InvalidContractRule)?;
+                }
+            }
+            Rule::LocaleNumber {
+                locale, min, max, ..
+            } => {
+                if number_locale(locale).is_none() {
+                    return Err(RunError::InvalidContractRule(format!(
+                        "Unsupported number locale '{locale}'."
+                    )));
+                }
+                if let (Some(min), Some(max)) = (min, max) {
+                    if min > max {
+                        return Err(RunError::InvalidContractRule(format!(
+                            "'locale_number' min ({min}) must not exceed max ({max})."
+                        )));
+                    }
+                }
+            }
+            Rule::GeoCoordinate {
+                field,
+                latitude,
+                longitude,
+                bbox,
+            } => {
+                let separate = latitude.is_some() && longitude.is_some();
+                let any_separate = latitude.is_some() || longitude.is_some();
+                if field.is_some() == any_separate || (any_separate && !separate) {
+                    return Err(RunError::InvalidContractRule(
+                        "'geo_coordinate' requires either 'field' or both 'latitude' and 'longitude'."
+                            .to_string(),
+                    ));
+                }
+                if let Some(bbox) = bbox {
+                    let valid = -90.0 <= bbox.min_lat
+                        && bbox.min_lat <= bbox.max_lat
+                        && bbox.max_lat <= 90.0
+                        && -180.0 <= bbox.min_lon
+                        && bbox.min_lon <= bbox.max_lon
+                        && bbox.max_lon <= 180.0;
+                    if !valid {
+                        return Err(RunError::InvalidContractRule(
+                            "'geo_coordinate' bbox must have min <= max within valid coordinate ranges."
+                                .to_string(),
+                        ));
+                    }
+                }
+            }
+            Rule::KeyOrder { keys, .. } => {
+                if keys.is_empty() {
+                    return Err(RunError::InvalidContractRule(
+                        "'key_order' requires at least one key.".to_string(),
+                    ));
+                }
+                if let Some((idx, key)) = keys
+                    .iter()
+                    .enumerate()
+                    .find(|(idx, key)| keys[..*idx].contains(key))
+                {
+                    return Err(RunError::InvalidContractRule(format!(
+                        "'key_order' lists key '{key}' twice (position {idx})."
+                    )));
+                }
+            }
+            Rule::FieldArrayLength { min, max, .. } | Rule::StringLength { min, max, .. } => {
+                match (min, max) {
+                    (None, None) => {
+                        return Err(RunError::InvalidContractRule(format!(
+                            "'{}' requires 'min' or 'max'.",
+                            rule.name()
+                        )));
+                    }
+                    (Some(min), Some(max)) if min > max => {
+                        return Err(RunError::InvalidContractRule(format!(
+                            "'{}' min ({min}) must not exceed max ({max}).",
+                            rule.name()
+                        )));
+                    }
+                    _ => {}
+                }
+            }
+            Rule::NumberRange { min, max, .. } => match (min, max) {
+                (None, None) => {
+                    return Err(RunError::InvalidContractRule(
+                        "'number_range' requires 'min' or 'max'.".to_string(),
+                    ));
+                }
+                (Some(min), Some(max)) if min > max => {
+                    return Err(RunError::InvalidContractRule(format!(
+                        "'number_range' min ({min}) must not exceed max ({max})."
+                    )));
+                }
+                _ => {}
+            },
+            Rule::ConsistentAcrossOutputs { key, fields, .. } => {
+                if fields.is_empty() {
+                    return Err(RunError::InvalidContractRule(
+                        "'consistent_across_outputs' requires at least one field.".to_string(),
+                    ));
+                }
+                if fields.contains(key) {
+                    return Err(RunError::InvalidContractRule(format!(
+                        "'consistent_across_outputs' key '{key}' must not be listed in 'fields'."
+                    )));
+                }
+            }
+            Rule::AnyOf { groups } | Rule::OneOf { groups } => {
+                if groups.is_empty() {
+                    let name = match rule {
+                        Rule::AnyOf { .. } => Combinator::AnyOf.rule(),
+                        _ => Combinator::OneOf.rule(),
+                    };
+                    return Err(RunError::InvalidContractRule(format!(
+                        "'{name}' requires at least one rule group."
+                    )));
+                }
+                for group in groups {
+                    validate_rules(contract, group)?;
+                }
+            }
+            Rule::Not { rules } => {
+                if rules.is_empty() {
+                    return Err(RunError::InvalidContractRule(format!(
+                        "'{}' requires at least one rule.",
+                        Combinator::Not.rule()
+                    )));
+                }
+                validate_rules(contract, rules)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn check_rule(ctx: &EvalContext, rule: &Rule, output: &Value, violations: &mut Vec<Violation>) {
+    match rule {
+        Rule::RequiredField { field, default } => {
+            check_required_field(field, default.as_ref(), output, violations)
+        }
+        Rule::FieldType { field, expected } => {
+            check_field_type(field, expected, output, violations)
+        }
+        Rule::AllowedValues {
+            field,
+            values,
+            values_file,
+            normalize,
+            ..
+        } => check_allowed_values(
+            field,
+            values,
+            values_file.as_deref(),
+            normalize.as_ref(),
+            ctx.contract.strict,
+            output,
+            violations,
+        ),
+        Rule::CoversValues {
+            field,
+            values,
+            normalize,
+        } => check_covers_values(field, values, normalize.as_ref(), output, violations),
+        Rule::Const {
+            field,
+            value,
+            normalize,
+        } => check_const(field, value, normalize.as_ref(), output, violations),
+        Rule::Regex {
+            field,
+            pattern,
+            pattern_ref,
+        } => {
+            let (pattern, regex) = ctx.regex(pattern, pattern_ref);
+            check_regex(
+                field,
+                pattern,
+                &regex,
+                ctx.contract.strict,
+                output,
+                violations,
+            )
+        }
+        Rule::MinItems { value } => check_min_items(*value, output, violations),
+        Rule::MaxItems { value } => check_max_items(*value, output, violations),
+        Rule::NoEmptyRows => check_no_empty_rows(output, violations),
+        Rule::KeyOrder { keys, exact } => check_key_order(keys, *exact, output, violations),
+        Rule::EmbeddedJson { field, expected } => {
+            check_embedded_json(field, expected, output, violations)
+        }
+        Rule::CheckDigit { field, scheme } => check_check_digit(field, *scheme, output, violations),
+        Rule::GeoCoordinate {
+            field,
+            latitude,
+            longitude,
+            bbox,
+        } => {
+            let fields = match (field, latitude, longitude) {
+                (Some(field), _, _) => GeoFields::Pair(field),
+                (None, Some(latitude), Some(longitude)) => GeoFields::Separate(latitude, longitude),
+                _ => unreachable!("geo_coordinate fields validated in run()"),
+            };
+            check_geo_coordinate(fields, bbox.as_ref(), output, violations)
+        }
+        Rule::FieldArrayLength { field, min, max } => {
+            check_field_array_length(field, (*min, *max), output, violations)
+        }
+        Rule::NumberRange { field, min, max } => {
+            check_number_range(field, (*min, *max), output, violations)
+        }
+        Rule::StringLength { field, min, max } => {
+            check_string_length(field, (*min, *max), output, violations)
+        }
+        Rule::UniqueValues { field, normalize } => {
+            check_unique_values(field, normalize.as_ref(), output, violations)
+        }
+        Rule::FieldComparison {
+            field,
+            operator,
+            other_field,
+        } => check_field_comparison(field, *operator, other_field, output, violations),
+        Rule::ApproxEqual {
+            field,
+            target,
+            other_field,
+            abs_tolerance,
+            rel_tolerance,
+        } => {
+            let target = match (target, other_field) {
+                (Some(value), _) => ApproxTarget::Value(*value),
+                (None, Some(other)) => ApproxTarget::Field(other),
+                (None, None) => unreachable!("approx_equal targets validated in run()"),
+            };
+            let tolerance = Tolerance {
+                absolute: abs_tolerance.unwrap_or(0.0),
+                relative: rel_tolerance.unwrap_or(0.0),
+            };
+            check_approx_equal(field, target, tolerance, output, violations)
+        }
+        Rule::DatetimeFormat {
+            field,
+            format,
+            preset,
+        } => {
+            let format = match (format, preset) {
+                (Some(format), _) => DatetimeFormat::Custom(format),
+                (None, Some(preset)) => DatetimeFormat::Preset(*preset),
+                (None, None) => unreachable!("datetime formats validated in run()"),
+            };
+            check_datetime_format(field, format, output, violations)
+        }
+        Rule::DatetimeOrder {
+            field,
+            other_field,
+            operator,
+            format,
+            timezone,
+        } => {
+            let offset = datetime::parse_offset(timezone.as_deref().unwrap_or("UTC"))
+                .expect("timezones validated in run()");
+            let order = DatetimeOrder {
+                field,
+                other_field,
+                operator: *operator,
+                format: format.as_deref(),
+                offset,
+            };
+            check_datetime_order(&order, output, violations)
+        }
+        Rule::LocaleNumber {
+            field,
+            locale,
+            min,
+            max,
+        } => {
+            let number_format = number_locale(locale).expect("locales validated in run()");
+            let bounds = (*min, *max);
+            check_locale_number(field, locale, number_format, bounds, output, violations)
+        }
+        Rule::UniqueAcrossOutputs { .. } | Rule::ConsistentAcrossOutputs { .. } => {
+            let results = check_across_outputs(rule, &[("output", output)]);
+            violations.extend(results.into_iter().flatten());
+        }
+        Rule::AnyOf { groups } => {
+            check_combinator(ctx, Combinator::AnyOf, groups, output, violations)
+        }
+        Rule::OneOf { groups } => {
+            check_combinator(ctx, Combinator::OneOf, groups, output, violations)
+        }
+        Rule::Not { rules } => check_combinator(
+            ctx,
+            Combinator::Not,
+            std::slice::from_ref(rules),
+            output,
+            violations,
+        ),
+    }
+}
+
+fn check_required_field(
+    field: &str,
+    default: Option<&Value>,
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    for_each_object(
+        "RequiredField",
+        output,
+        violations,
+        |map, row_index, violations| {
+            for found in path::resolve(map, field) {
+                if found.value.is_some() {
+                    continue;
+                }
+                let field = &*found.path;
+                let detail = match row_index {
+                    Some(idx) => format!("Row {idx} is missing required field '{field}'."),
+                    None => format!("Missing required field '{field}'."),
+                };
+                violations.push(required_field_violation(field, default, detail));
+            }
+        },
+    );
+}
+
+fn check_field_type(
+    field: &str,
+    expected: &ValueType,
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    for_each_object(
+        "FieldType",
+        output,
+        violations,
+        |map, row_index, violations| {
+            for found in path::resolve(map, field) {
+                check_field_type_value(&found.path, expected, found.value, row_index, violations)
+            }
+        },
+    );
+}
+
+fn check_field_type_value(
+    field: &str,
+    expected: &ValueType,
+    value: Option<&Value>,
+    row_index: Option<usize>,
+    violations: &mut Vec<Violation>,
+) {
+    match value {
+        Some(value) => {
+            if !matches_value_type(value, expected) {
+                let location = row_index
+                    .map(|i| format!("Row {i} field '{field}'"))
+                    .unwrap_or_else(|| format!("Field '{field}'"));
+                violations.push(Violation {
+                    field: Some(field.to_string()),
+                    expected: Some(Value::String(value_type_label(expected).to_string())),
+                    actual: Some(value.clone()),
+                    ..simple_violation(
+                        "FieldType",
+                        format!(
+                            "{location} expected type '{}', got '{}'.",
+                            value_type_label(expected),
+                            detected_value_type(value)
+                        ),
+                    )
+                });
+            }
+        }
+        None => {
+            let location = row_index
+                .map(|i| format!("Row {i}"))
+                .unwrap_or_else(|| "Object".to_string());
+            violations.push(Violation {
+                field: Some(field.to_string()),
+                ..simple_violation(
+                    "FieldType",
+                    format!("{location} is missing field '{field}' for type check."),
+                )
+                .with_code(codes::MISSING_TYPED_FIELD)
+            });
+        }
+    }
+}
+
+fn check_no_empty_rows(output: &Value, violations: &mut Vec<Violation>) {
+    match output {
+        Value::Array(rows) => {
+            for (idx, row) in rows.iter().enumerate() {
+                match row {
+                    Value::Object(map) => {
+                        if map.is_empty() || map.values().all(is_empty_value) {
+                            violations.push(Violation {
+                                row: Some(idx),
+                                ..simple_violation("NoEmptyRows", format!("Row {idx} is empty."))
+                            });
+                        }
+                    }
+                    _ => violations.push(Violation {
+                        row: Some(idx),
+                        ..shape_violation("NoEmptyRows", format!("Row {idx} is not an object."))
+                    }),
+                }
+            }
+        }
+        _ => violations.push(shape_violation(
+            "NoEmptyRows",
+            "NoEmptyRows requires top-level array output.".to_string(),
+        )),
+    }
+}
+
+// Compares key positions as written in the output, which relies on
+// serde_json's `preserve_order` keeping object keys in source order.
+fn check_key_order(keys: &[String], exact: bool, output: &Value, violations: &mut Vec<Violation>) {
+    for_each_object(
+        "KeyOrder",
+        output,
+        violations,
+        |map, row_index, violations| {
+            let actual: Vec<&str> = map
+                .keys()
+                .filter(|key| exact || keys.contains(key))
+                .map(String::as_str)
+                .collect();
+            let expected: Vec<&str> = keys
+                .iter()
+                .filter(|key| exact || map.contains_key(*key))
+                .map(String::as_str)
+                .collect();
+            if actual == expected {
+                return;
+            }
+            let location = row_index
+                .map(|i| format!("Row {i}"))
+                .unwrap_or_else(|| "Object".to_string());
+            let detail = if exact {
+                format!(
+                    "{location} must have exactly the keys {} in that order, found {}.",
+                    quoted_keys(&expected),
+                    quoted_keys(&actual)
+                )
+            } else {
+                format!(
+                    "{location} has keys out of order: expected {}, found {}.",
+                    quoted_keys(&expected),
+                    quoted_keys(&actual)
+                )
+            };
+            violations.push(Violation {
+                rule: Some("key_order".to_string()),
+                expected: Some(Value::from(expected)),
+                actual: Some(Value::from(actual)),
+                ..simple_violation("KeyOrder", detail)
+            });
+        },
+    );
+}
+
+fn quoted_keys(keys: &[&str]) -> String {
+    keys.iter()
+        .map(|key| format!("'{key}'"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn check_embedded_json(
+    field: &str,
+    expected: &ValueType,
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    for_each_field(
+        "EmbeddedJson",
+        field,
+        output,
+        violations,
+        |_, field, actual, row_index, violations| {
+            let location = field_location(field, row_index);
+            let (code, detail) = match actual.as_str().map(serde_json::from_str::<Value>) {
+                None => (
+                    codes::UNSUITABLE_VALUE_TYPE,
+                    format!("{location} must be a string for embedded_json rule."),
+                ),
+                Some(Err(err)) => (
+                    codes::INVALID_EMBEDDED_JSON,
+                    format!("{location} does not contain valid JSON: {err}."),
+                ),
+                Some(Ok(parsed)) if !matches_value_type(&parsed, expected) => (
+                    codes::INVALID_EMBEDDED_JSON,
+                    format!(
+                        "{location} embeds JSON of type '{}', expected '{}'.",
+                        detected_value_type(&parsed),
+                        value_type_label(expected)
+                    ),
+                ),
+                Some(Ok(_)) => return,
+            };
+            violations
+                .push(embedded_json_violation(field, expected, actual, detail).with_code(code));
+        },
+    );
+}
+
+fn check_check_digit(
+    field: &str,
+    scheme: CheckDigitScheme,
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    for_each_field(
+        "CheckDigit",
+        field,
+        output,
+        violations,
+        |_, field, actual, row_index, violations| {
+            let location = field_location(field, row_index);
+            let (code, detail) = match actual
+                .as_str()
+                .map(|code| checksum::check_digit(code, scheme))
+            {
+                Some(Ok(())) => return,
+                Some(Err(err)) => (
+                    codes::INVALID_CHECK_DIGIT,
+                    format!("{location} is not a valid {}: {err}.", scheme.name()),
+                ),
+                None => (
+                    codes::UNSUITABLE_VALUE_TYPE,
+                    format!("{location} must be a string for check_digit rule."),
+                ),
+            };
+            violations.push(check_digit_violation(field, scheme, actual, detail).with_code(code));
+        },
+    );
+}
+
+enum GeoFields<'a> {
+    Pair(&'a str),
+    Separate(&'a str, &'a str),
+}
+
+fn check_geo_coordinate(
+    fields: GeoFields,
+    bbox: Option<&BoundingBox>,
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    let expected = match bbox {
+        Some(bbox) => serde_json::to_value(bbox).expect("serialize bounding box"),
+        None => Value::String("valid latitude/longitude".to_string()),
+    };
+    for_each_object(
+        "GeoCoordinate",
+        output,
+        violations,
+        |map, row_index, violations| {
+            let (field, actual, coordinates) = match fields {
+                GeoFields::Pair(field) => {
+                    let Some(actual) = path::lookup(map, field) else {
+                        return;
+                    };
+                    let coordinates = match actual.as_array().map(Vec::as_slice) {
+                        Some([lat, lon]) => Some((lat, lon)),
+                        _ => None,
+                    };
+                    (field, actual.clone(), coordinates)
+                }
+                GeoFields::Separate(latitude, longitude) => {
+                    let (Some(lat), Some(lon)) =
+                        (path::lookup(map, latitude), path::lookup(map, longitude))
+                    else {
+                        return;
+                    };
+                    let actual = Value::Array(vec![lat.clone(), lon.clone()]);
+                    (latitude, actual, Some((lat, lon)))
+                }
+            };
+            let location = field_location(field, row_index);
+            let Some((lat, lon)) = coordinates else {
+                violations.push(
+                    geo_coordinate_violation(
+                        field,
+                        &expected,
+                        &actual,
+                        format!("{location} must be a [latitude, longitude] pair."),
+                    )
+                    .with_code(codes::UNSUITABLE_VALUE_TYPE),
+                );
+                return;
+            };
+            let (code, detail) = match (lat.as_f64(), lon.as_f64()) {
+                (Some(lat), Some(lon)) => match geo_coordinate_problem(lat, lon, bbox) {
+                    Some(problem) => (codes::INVALID_COORDINATE, format!("{location} {problem}.")),
+                    None => return,
+                },
+                _ => (
+                    codes::UNSUITABLE_VALUE_TYPE,
+                    format!("{location} must hold numeric coordinates."),
+                ),
+            };
+            let (latitude, longitude) = match fields {
+                GeoFields::Pair(field) => (field, field),
+                GeoFields::Separate(latitude, longitude) => (latitude, longitude),
+            };
+            violations.push(Violation {
+                quoted: vec![
+                    (latitude.to_string(), lat.clone()),
+                    (longitude.to_string(), lon.clone()),
+                ],
+                ..geo_coordinate_violation(field, &expected, &actual, detail).with_code(code)
+            });
+        },
+    );
+}
+
+fn geo_coordinate_problem(lat: f64, lon: f64, bbox: Option<&BoundingBox>) -> Option<String> {
+    if !(-90.0..=90.0).contains(&lat) {
+        return Some(format!("has latitude {lat} outside [-90, 90]"));
+    }
+    if !(-180.0..=180.0).contains(&lon) {
+        return Some(format!("has longitude {lon} outside [-180, 180]"));
+    }
+    let bbox = bbox?;
+    let inside = (bbox.min_lat..=bbox.max_lat).contains(&lat)
+        && (bbox.min_lon..=bbox.max_lon).contains(&lon);
+    (!inside).then(|| format!("at ({lat}, {lon}) lies outside the bounding box"))
+}
+
+fn check_field_array_length(
+    field: &str,
+    (min, max): (Option<u64>, Option<u64>),
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    let expected = length_range(min, max);
+    for_each_field(
+        "FieldArrayLength",
+        field,
+        output,
+        violations,
+        |_, field, actual, row_index, violations| {
+            let location = field_location(field, row_index);
+            let Some(items) = actual.as_array() else {
+                violations.push(
+                    field_array_length_violation(
+                        field,
+                        &expected,
+                        Value::String(detected_value_type(actual).to_string()),
+                        format!("{location} must be an array for field_array_length rule."),
+                    )
+                    .with_code(codes::UNSUITABLE_VALUE_TYPE),
+                );
+                return;
+            };
+            let len = items.len() as u64;
+            let bound = match (min, max) {
+                (Some(min), _) if len < min => format!("at least {min}"),
+                (_, Some(max)) if len > max => format!("at most {max}"),
+                _ => return,
+            };
+            violations.push(field_array_length_violation(
+                field,
+                &expected,
+                Value::from(len),
+                format!("{location} must contain {bound} items, found {len}."),
+            ));
+        },
+    );
+}
+
+fn check_number_range(
+    field: &str,
+    (min, max): (Option<f64>, Option<f64>),
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    let expected = json_range(min, max);
+    for_each_field(
+        "NumberRange",
+        field,
+        output,
+        violations,
+        |_, field, actual, row_index, violations| {
+            let location = field_location(field, row_index);
+            let Some(number) = actual.as_f64() else {
+                violations.push(
+                    number_range_violation(
+                        field,
+                        &expected,
+                        actual.clone(),
+                        format!("{location} must be a number for number_range rule."),
+                    )
+                    .with_code(codes::UNSUITABLE_VALUE_TYPE),
+                );
+                return;
+            };
+            let bound = match (min, max) {
+                (Some(min), _) if number < min => format!("at least {min}"),
+                (_, Some(max)) if number > max => format!("at most {max}"),
+                _ => return,
+            };
+            violations.push(number_range_violation(
+                field,
+                &expected,
+                actual.clone(),
+                format!("{location} must be {bound}, found {number}."),
+            ));
+        },
+    );
+}
+
+fn check_string_length(
+    field: &str,
+    (min, max): (Option<u64>, Option<u64>),
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    let expected = length_range(min, max);
+    for_each_field(
+        "StringLength",
+        field,
+        output,
+        violations,
+        |_, field, actual, row_index, violations| {
+            let location = field_location(field, row_index);
+            let Some(text) = actual.as_str() else {
+                violations.push(
+                    string_length_violation(
+                        field,
+                        &expected,
+                        Value::String(detected_value_type(actual).to_string()),
+                        format!("{location} must be a string for string_length rule."),
+                    )
+                    .with_code(codes::UNSUITABLE_VALUE_TYPE),
+                );
+                return;
+            };
+            let len = text.chars().count() as u64;
+            let bound = match (min, max) {
+                (Some(min), _) if len < min => format!("at least {min}"),
+                (_, Some(max)) if len > max => format!("at most {max}"),
+                _ => return,
+            };
+            violations.push(string_length_violation(
+                field,
+                &expected,
+                Value::from(len),
+                format!("{location} must be {bound} characters long, found {len}."),
+            ));
+        },
+    );
+}
+
+// The first occurrence of a value wins; every later one is reported where it
+// appears.
+fn check_unique_values(
+    field: &str,
+    normalize: Option<&Normalization>,
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    let mut first_seen: HashMap<String, (String, Option<usize>)> = HashMap::new();
+    for_each_field(
+        "UniqueValues",
+        field,
+        output,
+        violations,
+        |_, field, actual, row_index, violations| {
+            let key = comparable_value(actual, normalize).to_string();
+            match first_seen.get(&key) {
+                Some((first_field, first_row)) => {
+                    let first = match first_row {
+                        Some(row) => format!("'{first_field}' in row {row}"),
+                        None => format!("'{first_field}'"),
+                    };
+                    violations.push(unique_violation(
+                        "UniqueValues",
+                        "unique_values",
+                        field,
+                        actual,
+                        format!(
+                            "{} value {actual} duplicates {first}.",
+                            field_location(field, row_index)
+                        ),
+                    ));
+                }
+                None => {
+                    first_seen.insert(key, (field.to_string(), row_index));
+                }
+            }
+        },
+    );
+}
+
+// Numbers compare numerically and strings lexicographically; `==` and `!=`
+// also accept values of other or differing types.
+fn check_field_comparison(
+    field: &str,
+    operator: ComparisonOperator,
+    other_field: &str,
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    let expected = Value::String(format!("{} {other_field}", operator.symbol()));
+    for_each_field(
+        "FieldComparison",
+        field,
+        output,
+        violations,
+        |map, field, actual, row_index, violations| {
+            let Some(other) = path::lookup(map, other_field) else {
+                return;
+            };
+            let location = field_location(field, row_index);
+            let ordering = match (actual, other) {
+                (Value::Number(left), Value::Number(right)) => left
+                    .as_f64()
+                    .zip(right.as_f64())
+                    .and_then(|(left, right)| left.partial_cmp(&right)),
+                (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+                _ if matches!(operator, ComparisonOperator::Eq | ComparisonOperator::Ne) => {
+                    Some(if actual == other {
+                        Ordering::Equal
+                    } else {
+                        Ordering::Less
+                    })
+                }
+                _ => None,
+            };
+            let Some(ordering) = ordering else {
+                violations.push(
+                    field_comparison_violation(
+                        field,
+                        &expected,
+                        actual.clone(),
+                        format!(
+                            "{location} and field '{other_field}' must both be numbers or both strings for field_comparison rule."
+                        ),
+                    )
+                    .with_code(codes::UNSUITABLE_VALUE_TYPE),
+                );
+                return;
+            };
+            if !operator.holds(ordering) {
+                violations.push(Violation {
+                    quoted: vec![(other_field.to_string(), other.clone())],
+                    ..field_comparison_violation(
+                        field,
+                        &expected,
+                        actual.clone(),
+                        format!(
+                            "{location} must be {} field '{other_field}' ({other}).",
+                            operator.symbol()
+                        ),
+                    )
+                });
+            }
+        },
+    );
+}
+
+// Violations list the allowed values as `expected`, except those loaded from
+// a values file, which name the file: such lists run to thousands of values.
+fn check_allowed_values(
+    field: &str,
+    values: &[Value],
+    values_file: Option<&str>,
+    normalize: Option<&Normalization>,
+    strict: bool,
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    let comparable = comparable_values(values, normalize);
+    let expected = match values_file {
+        Some(values_file) => json!({ "values_file": values_file, "count": values.len() }),
+        None => Value::Array(values.to_vec()),
+    };
+    for_each_object(
+        "AllowedValues",
+        output,
+        violations,
+        |map, row_index, violations| {
+            for found in path::resolve(map, field) {
+                let field = &*found.path;
+                let Some(actual) = found.value else {
+                    if strict {
+                        violations.push(strict_missing_field_violation(
+                            "AllowedValues",
+                            field,
+                            row_index,
+                        ));
+                    }
+                    continue;
+                };
+                if !comparable.contains(&comparable_value(actual, normalize)) {
+                    violations.push(allowed_values_violation(
+                        field,
+                        &expected,
+                        actual,
+                        format!(
+                            "{} has a disallowed value.",
+                            field_location(field, row_index)
+                        ),
+                    ));
+                }
+            }
+        },
+    );
+}
+
+// The coverage-style inverse of `allowed_values`: rather than restricting
+// each row, it requires the rows together to use every listed value.
+fn check_covers_values(
+    field: &str,
+    values: &[Value],
+    normalize: Option<&Normalization>,
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    if !output.is_array() {
+        violations.push(shape_violation(
+            "CoversValues",
+            "CoversValues requires top-level array output.".to_string(),
+        ));
+        return;
+    }
+    let mut seen = Vec::new();
+    for_each_field(
+        "CoversValues",
+        field,
+        output,
+        violations,
+        |_, _, actual, _, _| {
+            seen.push(comparable_value(actual, normalize));
+        },
+    );
+    let missing: Vec<Value> = values
+        .iter()
+        .filter(|value| !seen.contains(&comparable_value(value, normalize)))
+        .cloned()
+        .collect();
+    if missing.is_empty() {
+        return;
+    }
+    let listed: Vec<String> = missing.iter().map(Value::to_string).collect();
+    violations.push(Violation {
+        field: Some(field.to_string()),
+        rule: Some("covers_values".to_string()),
+        expected: Some(Value::Array(values.to_vec())),
+        actual: Some(Value::Array(missing)),
+        ..simple_violation(
+            "CoversValues",
+            format!(
+                "Field '{field}' never takes the value(s) {} in any row.",
+                listed.join(", ")
+            ),
+        )
+    });
+}
+
+fn spans_outputs(rule: &Rule) -> bool {
+    matches!(
+        rule,
+        Rule::UniqueAcrossOutputs { .. } | Rule::ConsistentAcrossOutputs { .. }
+    )
+}
+
+// Evaluates a rule that spans all outputs of a run, returning the violations
+// attributed to each output.
+fn check_across_outputs(rule: &Rule, outputs: &[(&str, &Value)]) -> Vec<Vec<Violation>> {
+    match rule {
+        Rule::UniqueAcrossOutputs { field, normalize } => {
+            check_unique_across_outputs(field, normalize.as_ref(), outputs)
+        }
+        Rule::ConsistentAcrossOutputs {
+            key,
+            fields,
+            normalize,
+        } => check_consistent_across_outputs(key, fields, normalize.as_ref(), outputs),
+        _ => vec![Vec::new(); outputs.len()],
+    }
+}
+
+// The first occurrence of a value wins; every later occurrence, in output and
+// row order, is reported in the output where it appears.
+fn check_unique_across_outputs(
+    field: &str,
+    normalize: Option<&Normalization>,
+    outputs: &[(&str, &Value)],
+) -> Vec<Vec<Violation>> {
+    let mut first_seen: HashMap<String, (usize, Option<usize>)> = HashMap::new();
+    let describe = |output_idx, row_index| describe_row(outputs, output_idx, row_index);
+
+    outputs
+        .iter()
+        .enumerate()
+        .map(|(output_idx, (_, output))| {
+            let mut violations = Vec::new();
+            for_each_field(
+                "UniqueAcrossOutputs",
+                field,
+                output,
+                &mut violations,
+                |_, field, actual, row_index, violations| {
+                    let key = comparable_value(actual, normalize).to_string();
+                    match first_seen.get(&key) {
+                        Some(&(first_output, first_row)) => {
+                            violations.push(unique_violation(
+                                "UniqueAcrossOutputs",
+                                "unique_across_outputs",
+                                field,
+                                actual,
+                                format!(
+                                    "{} value {actual} duplicates {}.",
+                                    field_location(field, row_index),
+                                    describe(first_output, first_row)
+                                ),
+                            ));
+                        }
+                        None => {
+                            first_seen.insert(key, (output_idx, row_index));
+                        }
+                    }
+                },
+            );
+            violations
+        })
+        .collect()
+}
+
+// The first row carrying a key value fixes the values of the other fields for
+// that key; later rows that disagree are reported where they appear. Absent
+// fields are skipped.
+fn check_consistent_across_outputs(
+    key: &str,
+    fields: &[String],
+    normalize: Option<&Normalization>,
+    outputs: &[(&str, &Value)],
+) -> Vec<Vec<Violation>> {
+    let mut first_seen: HashMap<(String, &str), (Value, usize, Option<usize>)> = HashMap::new();
+
+    outputs
+        .iter()
+        .enumerate()
+        .map(|(output_idx, (_, output))| {
+            let mut violations = Vec::new();
+            for_each_object(
+                "ConsistentAcrossOutputs",
+                output,
+                &mut violations,
+                |map, row_index, violations| {
+                    let Some(key_value) = path::lookup(map, key) else {
+                        return;
+                    };
+                    let key_text = comparable_value(key_value, normalize).to_string();
+                    for field in fields {
+                        let Some(actual) = path::lookup(map, field) else {
+                            continue;
+                        };
+                        let slot = (key_text.clone(), field.as_str());
+                        let Some((expected, first_output, first_row)) = first_seen.get(&slot)
+                        else {
+                            first_seen.insert(slot, (actual.clone(), output_idx, row_index));
+                            continue;
+                        };
+                        if comparable_value(actual, normalize)
+                            != comparable_value(expected, normalize)
+                        {
+                            violations.push(Violation {
+                                quoted: vec![(key.to_string(), key_value.clone())],
+                                ..consistent_violation(
+                                    field,
+                                    expected,
+                                    actual,
+                                    format!(
+                                        "{} value {actual} differs from {expected} at {} for '{key}' {key_value}.",
+                                        field_location(field, row_index),
+                                        describe_row(outputs, *first_output, *first_row)
+                                    ),
+                                )
+                            });
+                        }
+                    }
+                },
+            );
+            violations
+        })
+        .collect()
+}
+
+// Names a row for messages of rules spanning outputs; the output label is only
+// mentioned when the run verifies more than one output.
+fn describe_row(outputs: &[(&str, &Value)], output_idx: usize, row_index: Option<usize>) -> String {
+    let row = row_index
+        .map(|idx| format!("row {idx}"))
+        .unwrap_or_else(|| "the object".to_string());
+    if outputs.len() > 1 {
+        format!("{row} of output '{}'", outputs[output_idx].0)
+    } else {
+        row
+    }
+}
+
+fn check_const(
+    field: &str,
+    expected: &Value,
+    normalize: Option<&Normalization>,
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    let comparable = comparable_value(expected, normalize);
+    for_each_field(
+        "Const",
+        field,
+        output,
+        violations,
+        |_, field, actual, row_index, violations| {
+            if comparable_value(actual, normalize) != comparable {
+                violations.push(const_violation(
+                    field,
+                    expected,
+                    actual,
+                    format!(
+                        "{} must equal {expected}.",
+                        field_location(field, row_index)
+                    ),
+                ));
+            }
+        },
+    );
+}
+
+fn comparable_values(values: &[Value], normalize: Option<&Normalization>) -> Vec<Value> {
+    values
+        .iter()
+        .map(|value| comparable_value(value, normalize))
+        .collect()
+}
+
+fn comparable_value(value: &Value, normalize: Option<&Normalization>) -> Value {
+    match normalize {
+        Some(options) => normalize_value(value, options),
+        None => value.clone(),
+    }
+}
+
+fn check_regex(
+    field: &str,
+    pattern: &str,
+    regex: &Regex,
+    strict: bool,
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    for_each_object("Regex", output, violations, |map, row_index, violations| {
+        for found in path::resolve(map, field) {
+            match found.value {
+                Some(actual) => {
+                    check_regex_value(&found.path, pattern, regex, actual, row_index, violations)
+                }
+                None if strict => violations.push(strict_missing_field_violation(
+                    "Regex",
+                    &found.path,
+                    row_index,
+                )),
+                None => {}
+            }
         }
+    });
+}
+
+// Strict mode: rules that skip absent fields report them instead.
+fn strict_missing_field_violation(
+    rule_name: &str,
+    field: &str,
+    row_index: Option<usize>,
+) -> Violation {
+    Violation {
+        field: Some(field.to_string()),
+        ..simple_violation(
+            rule_name,
+            format!("{} is missing.", field_location(field, row_index)),
+        )
+        .with_code(codes::MISSING_FIELD)
     }
-    Ok(())
 }
 
-fn check_rule(rule: &Rule, output: &Value, violations: &mut Vec<Violation>) {
-    match rule {
-        Rule::RequiredField { field } => check_required_field(field, output, violations),
-        Rule::FieldType { field, expected } => {
-            check_field_type(field, expected, output, violations)
+// Strict mode: every key of every row (or of the object output) must be read
+// by some rule, as long as the contract names any fields at all.
+fn check_unknown_fields(contract: &Contract, output: &Value, violations: &mut Vec<Violation>) {
+    // A nested path reads the row key it starts at.
+    let known: Vec<String> = contract
+        .rule_specs()
+        .flat_map(|(_, spec)| spec.rule.referenced_fields())
+        .flat_map(|field| [Some(field.to_string()), path::root_key(field)])
+        .flatten()
+        .collect();
+    if known.is_empty() {
+        return;
+    }
+    let rows: Vec<(Option<usize>, &serde_json::Map<String, Value>)> = match output {
+        Value::Object(map) => vec![(None, map)],
+        Value::Array(rows) => rows
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, row)| Some((Some(idx), row.as_object()?)))
+            .collect(),
+        _ => Vec::new(),
+    };
+    for (row, map) in rows {
+        for key in map.keys().filter(|key| !known.contains(key)) {
+            violations.push(Violation {
+                field: Some(key.clone()),
+                row,
+                ..Violation::new(
+                    "UnknownField",
+                    format!("{} is not mentioned by any rule.", field_location(key, row)),
+                )
+            });
+        }
+    }
+}
+
+fn check_regex_value(
+    field: &str,
+    pattern: &str,
+    regex: &Regex,
+    actual: &Value,
+    row_index: Option<usize>,
+    violations: &mut Vec<Violation>,
+) {
+    match actual {
+        Value::String(s) => {
+            if !regex.is_match(s) {
+                let detail = row_index
+                    .map(|idx| format!("Row {idx} field '{field}' does not match regex pattern."))
+                    .unwrap_or_else(|| format!("Field '{field}' does not match regex pattern."));
+                violations.push(regex_violation(field, pattern, actual, detail));
+            }
         }
-        Rule::AllowedValues { field, values } => {
-            check_allowed_values(field, values, output, violations)
+        _ => {
+            let detail = row_index
+                .map(|idx| format!("Row {idx} field '{field}' must be a string for regex rule."))
+                .unwrap_or_else(|| format!("Field '{field}' must be a string for regex rule."));
+            violations.push(
+                regex_violation(field, pattern, actual, detail)
+                    .with_code(codes::UNSUITABLE_VALUE_TYPE),
+            );
         }
-        Rule::Regex { field, pattern } => check_regex(field, pattern, output, violations),
-        Rule::MinItems { value } => check_min_items(*value, output, violations),
-        Rule::NoEmptyRows => check_no_empty_rows(output, violations),
     }
 }
 
-fn check_required_field(field: &str, output: &Value, violations: &mut Vec<Violation>) {
+fn check_min_items(value: u64, output: &Value, violations: &mut Vec<Violation>) {
     match output {
-        Value::Object(map) => {
-            if !map.contains_key(field) {
-                violations.push(simple_violation(
-                    "RequiredField",
-                    format!("Missing required field '{field}'."),
+        Value::Array(items) => {
+            let actual_len = items.len() as u64;
+            if actual_len < value {
+                violations.push(min_items_violation(
+                    value,
+                    Value::from(actual_len),
+                    format!(
+                        "Top-level array must contain at least {value} items, found {actual_len}."
+                    ),
                 ));
             }
         }
-        Value::Array(rows) => {
-            for (idx, row) in rows.iter().enumerate() {
-                match row {
-                    Value::Object(map) => {
-                        if !map.contains_key(field) {
-                            violations.push(simple_violation(
-                                "RequiredField",
-                                format!("Row {idx} is missing required field '{field}'."),
-                            ));
-                        }
-                    }
-                    _ => violations.push(simple_violation(
-                        "RequiredField",
-                        format!("Row {idx} is not an object."),
-                    )),
-                }
+        _ => {
+            violations.push(
+                min_items_violation(
+                    value,
+                    Value::String(detected_value_type(output).to_string()),
+                    "MinItems requires top-level array output.".to_string(),
+                )
+                .with_code(codes::UNSUITABLE_SHAPE),
+            );
+        }
+    }
+}
+
+fn check_max_items(value: u64, output: &Value, violations: &mut Vec<Violation>) {
+    match output {
+        Value::Array(items) => {
+            let actual_len = items.len() as u64;
+            if actual_len > value {
+                violations.push(max_items_violation(
+                    value,
+                    Value::from(actual_len),
+                    format!(
+                        "Top-level array must contain at most {value} items, found {actual_len}."
+                    ),
+                ));
             }
         }
-        _ => violations.push(simple_violation(
-            "RequiredField",
-            "Output must be an object or an array of objects.".to_string(),
-        )),
+        _ => {
+            violations.push(
+                max_items_violation(
+                    value,
+                    Value::String(detected_value_type(output).to_string()),
+                    "MaxItems requires top-level array output.".to_string(),
+                )
+                .with_code(codes::UNSUITABLE_SHAPE),
+            );
+        }
     }
 }
 
-fn check_field_type(
+enum ApproxTarget<'a> {
+    Value(f64),
+    Field(&'a str),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Tolerance {
+    absolute: f64,
+    relative: f64,
+}
+
+impl Tolerance {
+    fn accepts(self, actual: f64, expected: f64) -> bool {
+        let scale = actual.abs().max(expected.abs());
+        (actual - expected).abs() <= self.absolute.max(self.relative * scale)
+    }
+}
+
+fn check_approx_equal(
     field: &str,
-    expected: &ValueType,
+    target: ApproxTarget,
+    tolerance: Tolerance,
     output: &Value,
     violations: &mut Vec<Violation>,
 ) {
-    match output {
-        Value::Object(map) => check_field_type_in_map(field, expected, map, None, violations),
-        Value::Array(rows) => {
-            for (idx, row) in rows.iter().enumerate() {
-                match row {
-                    Value::Object(map) => {
-                        check_field_type_in_map(field, expected, map, Some(idx), violations)
+    for_each_field(
+        "ApproxEqual",
+        field,
+        output,
+        violations,
+        |map, field, actual, row_index, violations| {
+            let location = field_location(field, row_index);
+            let Some(actual_number) = actual.as_f64() else {
+                violations.push(
+                    approx_equal_violation(
+                        field,
+                        None,
+                        actual,
+                        format!("{location} must be a number for approx_equal rule."),
+                    )
+                    .with_code(codes::UNSUITABLE_VALUE_TYPE),
+                );
+                return;
+            };
+
+            let (expected, description, quoted) = match target {
+                ApproxTarget::Value(value) => (value, format!("{value}"), Vec::new()),
+                ApproxTarget::Field(other) => match path::lookup(map, other).map(Value::as_f64) {
+                    None => return,
+                    Some(Some(value)) => (
+                        value,
+                        format!("field '{other}' ({value})"),
+                        vec![(other.to_string(), Value::from(value))],
+                    ),
+                    Some(None) => {
+                        violations.push(approx_equal_violation(
+                        field,
+                        None,
+                        actual,
+                        format!("{location} is compared to field '{other}', which is not a number."),
+                    ).with_code(codes::UNSUITABLE_VALUE_TYPE));
+                        return;
                     }
-                    _ => violations.push(simple_violation(
-                        "FieldType",
-                        format!("Row {idx} is not an object."),
-                    )),
-                }
+                },
+            };
+
+            if !tolerance.accepts(actual_number, expected) {
+                violations.push(Violation {
+                    quoted,
+                    ..approx_equal_violation(
+                        field,
+                        Some(Value::from(expected)),
+                        actual,
+                        format!("{location} is not approximately equal to {description}."),
+                    )
+                });
             }
-        }
-        _ => violations.push(simple_violation(
-            "FieldType",
-            "Output must be an object or an array of objects.".to_string(),
-        )),
-    }
+        },
+    );
 }
 
-fn check_field_type_in_map(
+fn check_datetime_format(
     field: &str,
-    expected: &ValueType,
-    map: &serde_json::Map<String, Value>,
-    row_index: Option<usize>,
+    format: DatetimeFormat,
+    output: &Value,
     violations: &mut Vec<Violation>,
 ) {
-    match map.get(field) {
-        Some(value) => {
-            if !matches_value_type(value, expected) {
-                let location = row_index
-                    .map(|i| format!("Row {i} field '{field}'"))
-                    .unwrap_or_else(|| format!("Field '{field}'"));
-                violations.push(simple_violation(
-                    "FieldType",
+    for_each_field(
+        "DatetimeFormat",
+        field,
+        output,
+        violations,
+        |_, field, actual, row_index, violations| {
+            let location = field_location(field, row_index);
+            let (code, detail) = match actual {
+                Value::String(s) => match datetime::check_datetime(s, format) {
+                    Ok(()) => return,
+                    Err(err) => (
+                        codes::INVALID_DATETIME,
+                        format!(
+                            "{location} does not match datetime format '{}': {err}.",
+                            format.label()
+                        ),
+                    ),
+                },
+                _ => (
+                    codes::UNSUITABLE_VALUE_TYPE,
+                    format!("{location} must be a string for datetime_format rule."),
+                ),
+            };
+            violations
+                .push(datetime_format_violation(field, format, actual, detail).with_code(code));
+        },
+    );
+}
+
+struct DatetimeOrder<'a> {
+    field: &'a str,
+    other_field: &'a str,
+    operator: ComparisonOperator,
+    format: Option<&'a str>,
+    offset: chrono::FixedOffset,
+}
+
+fn check_datetime_order(order: &DatetimeOrder, output: &Value, violations: &mut Vec<Violation>) {
+    let DatetimeOrder {
+        field,
+        other_field,
+        operator,
+        ..
+    } = *order;
+    for_each_object(
+        "DatetimeOrder",
+        output,
+        violations,
+        |map, row_index, violations| {
+            let (Some(actual), Some(other)) =
+                (path::lookup(map, field), path::lookup(map, other_field))
+            else {
+                return;
+            };
+
+            let mut instants = Vec::with_capacity(2);
+            for (name, value) in [(field, actual), (other_field, other)] {
+                let parsed = value
+                    .as_str()
+                    .ok_or_else(|| "expected a string".to_string())
+                    .and_then(|s| datetime::parse_instant(s, order.format, order.offset));
+                match parsed {
+                    Ok(instant) => instants.push(instant),
+                    Err(err) => {
+                        violations.push(
+                            datetime_order_violation(
+                                field,
+                                operator,
+                                (other_field, other),
+                                actual,
+                                format!(
+                                    "{} is not a valid datetime: {err}.",
+                                    field_location(name, row_index)
+                                ),
+                            )
+                            .with_code(codes::INVALID_DATETIME),
+                        );
+                        return;
+                    }
+                }
+            }
+
+            if !operator.holds(instants[0].cmp(&instants[1])) {
+                violations.push(datetime_order_violation(
+                    field,
+                    operator,
+                    (other_field, other),
+                    actual,
                     format!(
-                        "{location} expected type '{}', got '{}'.",
-                        value_type_label(expected),
-                        detected_value_type(value)
+                        "{} must be {} field '{other_field}'.",
+                        field_location(field, row_index),
+                        ordering_phrase(operator)
                     ),
                 ));
             }
-        }
-        None => {
-            let location = row_index
-                .map(|i| format!("Row {i}"))
-                .unwrap_or_else(|| "Object".to_string());
-            violations.push(simple_violation(
-                "FieldType",
-                format!("{location} is missing field '{field}' for type check."),
-            ));
-        }
+        },
+    );
+}
+
+fn ordering_phrase(operator: ComparisonOperator) -> &'static str {
+    match operator {
+        ComparisonOperator::Lt => "before",
+        ComparisonOperator::Lte => "at or before",
+        ComparisonOperator::Gt => "after",
+        ComparisonOperator::Gte => "at or after",
+        ComparisonOperator::Eq => "at the same instant as",
+        ComparisonOperator::Ne => "at a different instant than",
     }
 }
 
-fn check_no_empty_rows(output: &Value, violations: &mut Vec<Violation>) {
+fn check_locale_number(
+    field: &str,
+    locale: &str,
+    number_format: NumberLocale,
+    (min, max): (Option<f64>, Option<f64>),
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    for_each_field(
+        "LocaleNumber",
+        field,
+        output,
+        violations,
+        |_, field, actual, row_index, violations| {
+            let location = field_location(field, row_index);
+            let Some(text) = actual.as_str() else {
+                violations.push(
+                    locale_number_violation(
+                        field,
+                        Value::String(locale.to_string()),
+                        actual,
+                        format!("{location} must be a string for locale_number rule."),
+                    )
+                    .with_code(codes::UNSUITABLE_VALUE_TYPE),
+                );
+                return;
+            };
+            let Some(parsed) = number_format.parse(text) else {
+                violations.push(locale_number_violation(
+                    field,
+                    Value::String(locale.to_string()),
+                    actual,
+                    format!("{location} is not a number string in locale '{locale}'."),
+                ));
+                return;
+            };
+
+            let bound = match (min, max) {
+                (Some(min), _) if parsed < min => format!("at least {min}"),
+                (_, Some(max)) if parsed > max => format!("at most {max}"),
+                _ => return,
+            };
+            violations.push(
+                locale_number_violation(
+                    field,
+                    json_range(min, max),
+                    actual,
+                    format!("{location} value {parsed} must be {bound}."),
+                )
+                .with_code(codes::NUMBER_OUT_OF_RANGE),
+            );
+        },
+    );
+}
+
+fn length_range(min: Option<u64>, max: Option<u64>) -> Value {
+    let mut range = serde_json::Map::new();
+    if let Some(min) = min {
+        range.insert("min".to_string(), Value::from(min));
+    }
+    if let Some(max) = max {
+        range.insert("max".to_string(), Value::from(max));
+    }
+    Value::Object(range)
+}
+
+fn json_range(min: Option<f64>, max: Option<f64>) -> Value {
+    let mut range = serde_json::Map::new();
+    if let Some(min) = min {
+        range.insert("min".to_string(), Value::from(min));
+    }
+    if let Some(max) = max {
+        range.insert("max".to_string(), Value::from(max));
+    }
+    Value::Object(range)
+}
+
+// Runs `check` on an object output, or on each row of an array output, and
+// reports rows or outputs that are not objects. Violations raised for a row
+// are tagged with its index.
+fn for_each_object(
+    rule_name: &str,
+    output: &Value,
+    violations: &mut Vec<Violation>,
+    mut check: impl FnMut(&serde_json::Map<String, Value>, Option<usize>, &mut Vec<Violation>),
+) {
     match output {
+        Value::Object(map) => check(map, None, violations),
         Value::Array(rows) => {
             for (idx, row) in rows.iter().enumerate() {
+                let before = violations.len();
                 match row {
-                    Value::Object(map) => {
-                        if map.is_empty() || map.values().all(is_empty_value) {
-                            violations.push(simple_violation(
-                                "NoEmptyRows",
-                                format!("Row {idx} is empty."),
-                            ));
-                        }
-                    }
-                    _ => violations.push(simple_violation(
-                        "NoEmptyRows",
+                    Value::Object(map) => check(map, Some(idx), violations),
+                    _ => violations.push(shape_violation(
+                        rule_name,
                         format!("Row {idx} is not an object."),
                     )),
                 }
+                tag_row(&mut violations[before..], idx);
             }
         }
-        _ => violations.push(simple_violation(
-            "NoEmptyRows",
-            "NoEmptyRows requires top-level array output.".to_string(),
+        _ => violations.push(shape_violation(
+            rule_name,
+            "Output must be an object or an array of objects.".to_string(),
         )),
     }
 }
 
-fn check_allowed_values(
+fn tag_row(violations: &mut [Violation], row: usize) {
+    for violation in violations {
+        violation.row = Some(row);
+    }
+}
+
+// Runs `check` on every value `field` leads to in the object output or in
+// each row, passing the row and the value's concrete path. Missing values are
+// skipped.
+fn for_each_field(
+    rule_name: &str,
     field: &str,
-    values: &[Value],
     output: &Value,
     violations: &mut Vec<Violation>,
+    mut check: impl FnMut(
+        &serde_json::Map<String, Value>,
+        &str,
+        &Value,
+        Option<usize>,
+        &mut Vec<Violation>,
+    ),
 ) {
-    match output {
-        Value::Object(map) => {
-            if let Some(actual) = map.get(field) {
-                if !values.iter().any(|allowed| allowed == actual) {
-                    violations.push(allowed_values_violation(
-                        field,
-                        values,
-                        actual,
-                        format!("Field '{field}' has a disallowed value."),
-                    ));
+    for_each_object(
+        rule_name,
+        output,
+        violations,
+        |map, row_index, violations| {
+            for found in path::resolve(map, field) {
+                if let Some(actual) = found.value {
+                    check(map, &found.path, actual, row_index, violations);
                 }
             }
+        },
+    );
+}
+
+fn field_location(field: &str, row_index: Option<usize>) -> String {
+    row_index
+        .map(|idx| format!("Row {idx} field '{field}'"))
+        .unwrap_or_else(|| format!("Field '{field}'"))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Combinator {
+    AnyOf,
+    OneOf,
+    Not,
+}
+
+impl Combinator {
+    fn rule_name(self) -> &'static str {
+        match self {
+            Combinator::AnyOf => "AnyOf",
+            Combinator::OneOf => "OneOf",
+            Combinator::Not => "Not",
         }
-        Value::Array(rows) => {
-            for (idx, row) in rows.iter().enumerate() {
-                match row {
-                    Value::Object(map) => {
-                        if let Some(actual) = map.get(field) {
-                            if !values.iter().any(|allowed| allowed == actual) {
-                                violations.push(allowed_values_violation(
-                                    field,
-                                    values,
-                                    actual,
-                                    format!("Row {idx} field '{field}' has a disallowed value."),
-                                ));
-                            }
-                        }
-                    }
-                    _ => violations.push(simple_violation(
-                        "AllowedValues",
-                        format!("Row {idx} is not an object."),
-                    )),
-                }
-            }
+    }
+
+    fn rule(self) -> &'static str {
+        match self {
+            Combinator::AnyOf => "any_of",
+            Combinator::OneOf => "one_of",
+            Combinator::Not => "not",
         }
-        _ => violations.push(simple_violation(
-            "AllowedValues",
-            "Output must be an object or an array of objects.".to_string(),
-        )),
     }
 }
 
-fn check_regex(field: &str, pattern: &str, output: &Value, violations: &mut Vec<Violation>) {
-    let regex = Regex::new(pattern).expect("regex patterns validated in run()");
+// Combinators are evaluated per row for array outputs so that alternative
+// shapes can differ from row to row. `not` is a combinator over a single group.
+fn check_combinator(
+    ctx: &EvalContext,
+    combinator: Combinator,
+    groups: &[Vec<Rule>],
+    output: &Value,
+    violations: &mut Vec<Violation>,
+) {
     match output {
-        Value::Object(map) => check_regex_in_map(field, pattern, &regex, map, None, violations),
+        Value::Object(_) => {
+            check_combinator_in_value(ctx, combinator, groups, output, None, violations)
+        }
         Value::Array(rows) => {
             for (idx, row) in rows.iter().enumerate() {
                 match row {
-                    Value::Object(map) => {
-                        check_regex_in_map(field, pattern, &regex, map, Some(idx), violations)
-                    }
-                    _ => violations.push(simple_violation(
-                        "Regex",
-                        format!("Row {idx} is not an object."),
-                    )),
+                    Value::Object(_) => check_combinator_in_value(
+                        ctx,
+                        combinator,
+                        groups,
+                        row,
+                        Some(idx),
+                        violations,
+                    ),
+                    _ => violations.push(Violation {
+                        row: Some(idx),
+                        ..shape_violation(
+                            combinator.rule_name(),
+                            format!("Row {idx} is not an object."),
+                        )
+                    }),
                 }
             }
         }
-        _ => violations.push(simple_violation(
-            "Regex",
+        _ => violations.push(shape_violation(
+            combinator.rule_name(),
             "Output must be an object or an array of objects.".to_string(),
         )),
     }
 }
 
-fn check_regex_in_map(
-    field: &str,
-    pattern: &str,
-    regex: &Regex,
-    map: &serde_json::Map<String, Value>,
+fn check_combinator_in_value(
+    ctx: &EvalContext,
+    combinator: Combinator,
+    groups: &[Vec<Rule>],
+    value: &Value,
     row_index: Option<usize>,
     violations: &mut Vec<Violation>,
 ) {
-    let Some(actual) = map.get(field) else {
-        return;
+    let failures: Vec<Vec<Violation>> = groups
+        .iter()
+        .map(|group| evaluate_group(ctx, group, value))
+        .collect();
+    let passing = match combinator {
+        Combinator::Not => groups
+            .iter()
+            .filter(|group| group_matches(ctx, group, value))
+            .count(),
+        _ => failures.iter().filter(|failed| failed.is_empty()).count(),
     };
+    let location = row_index
+        .map(|i| format!("Row {i}"))
+        .unwrap_or_else(|| "Object".to_string());
+    let name = combinator.rule();
 
-    match actual {
-        Value::String(s) => {
-            if !regex.is_match(s) {
-                let detail = row_index
-                    .map(|idx| format!("Row {idx} field '{field}' does not match regex pattern."))
-                    .unwrap_or_else(|| format!("Field '{field}' does not match regex pattern."));
-                violations.push(regex_violation(field, pattern, actual, detail));
-            }
+    let mut quoted = Vec::new();
+    let detail = match combinator {
+        Combinator::Not if passing > 0 => {
+            format!("{location} matches the rules negated by {name}.")
         }
-        _ => {
-            let detail = row_index
-                .map(|idx| format!("Row {idx} field '{field}' must be a string for regex rule."))
-                .unwrap_or_else(|| format!("Field '{field}' must be a string for regex rule."));
-            violations.push(regex_violation(field, pattern, actual, detail));
+        Combinator::Not => return,
+        _ if passing == 0 => {
+            quoted = failures.iter().flatten().flat_map(quoted_values).collect();
+            format!(
+                "{location} matches none of the {name} groups ({}).",
+                summarize_group_failures(&failures)
+            )
         }
+        Combinator::OneOf if passing > 1 => {
+            format!("{location} matches {passing} {name} groups; exactly one is allowed.")
+        }
+        _ => return,
+    };
+    violations.push(Violation {
+        row: row_index,
+        quoted,
+        ..combinator_violation(combinator, passing, detail)
+    });
+}
+
+// The values a nested violation's message may show, for the message of the
+// combinator it failed in.
+fn quoted_values(violation: &Violation) -> Vec<(String, Value)> {
+    let own = violation.field.iter().flat_map(|field| {
+        [&violation.actual, &violation.expected]
+            .into_iter()
+            .flatten()
+            .map(|value| (field.clone(), value.clone()))
+    });
+    own.chain(violation.quoted.iter().cloned()).collect()
+}
+
+fn evaluate_group(ctx: &EvalContext, rules: &[Rule], value: &Value) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for rule in rules {
+        check_rule(ctx, rule, value, &mut violations);
     }
+    violations
 }
 
-fn check_min_items(value: u64, output: &Value, violations: &mut Vec<Violation>) {
-    match output {
-        Value::Array(items) => {
-            let actual_len = items.len() as u64;
-            if actual_len < value {
-                violations.push(min_items_violation(
-                    value,
-                    Value::from(actual_len),
-                    format!(
-                        "Top-level array must contain at least {value} items, found {actual_len}."
-                    ),
-                ));
-            }
+// Whether `value` matches every rule of the group without passing only
+// because a field the rule reads is absent: what `not` negates. A row without
+// the field does not match the rule, as in strict mode.
+fn group_matches(ctx: &EvalContext, rules: &[Rule], value: &Value) -> bool {
+    rules.iter().all(|rule| match rule {
+        Rule::AnyOf { groups } => groups.iter().any(|group| group_matches(ctx, group, value)),
+        Rule::OneOf { groups } => {
+            groups
+                .iter()
+                .filter(|group| group_matches(ctx, group, value))
+                .count()
+                == 1
         }
+        Rule::Not { rules } => !group_matches(ctx, rules, value),
         _ => {
-            violations.push(min_items_violation(
-                value,
-                Value::String(detected_value_type(output).to_string()),
-                "MinItems requires top-level array output.".to_string(),
-            ));
+            let present = value.as_object().is_some_and(|map| {
+                rule.referenced_fields().into_iter().all(|field| {
+                    path::resolve(map, field)
+                        .iter()
+                        .all(|found| found.value.is_some())
+                })
+            });
+            present && evaluate_group(ctx, std::slice::from_ref(rule), value).is_empty()
         }
-    }
+    })
+}
+
+fn summarize_group_failures(failures: &[Vec<Violation>]) -> String {
+    failures
+        .iter()
+        .enumerate()
+        .map(|(idx, failed)| {
+            let details: Vec<&str> = failed.iter().map(|v| v.detail.as_str()).collect();
+            format!("group {}: {}", idx + 1, details.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 fn matches_value_type(value: &Value, expected: &ValueType) -> bool {
@@ -463,7 +3620,7 @@ fn value_type_label(value_type: &ValueType) -> &'static str {
     }
 }
 
-fn detected_value_type(value: &Value) -> &'static str {
+pub fn detected_value_type(value: &Value) -> &'static str {
     if value.is_string() {
         "string"
     } else if value.is_number() {
@@ -0,0 +1,267 @@
+//! Field paths. Rules name nested values with dot/bracket paths such as
+//! `result.items[*].meta.score` or with JSON Pointers such as
+//! `/result/items/0/meta/score`. `[*]` (or a `*` pointer token) visits every
+//! item of an array or value of an object, and every value a path leads to
+//! is reported under its concrete path, e.g. `result.items[3].meta.score`.
+
+use std::borrow::Cow;
+
+use serde_json::{Map, Value};
+
+use crate::spans::PathSegment;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Notation {
+    Dotted,
+    Pointer,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FieldPath {
+    notation: Notation,
+    steps: Vec<Step>,
+}
+
+/// A value a field path leads to, or the place where it stops.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FieldMatch<'a> {
+    /// The path with wildcards replaced by the indexes or keys visited. When
+    /// the value is missing, the unresolved remainder is kept as written.
+    pub path: Cow<'a, str>,
+    pub value: Option<&'a Value>,
+}
+
+/// Whether `field` is a path rather than a plain key.
+pub(crate) fn is_path(field: &str) -> bool {
+    field.starts_with('/') || field.contains(['.', '['])
+}
+
+/// Checks the syntax of `field`, describing the first problem.
+pub(crate) fn validate(field: &str) -> Result<(), String> {
+    if is_path(field) {
+        FieldPath::parse(field).map(drop)
+    } else {
+        Ok(())
+    }
+}
+
+/// Every value `field` leads to in `map`, with one missing match for each
+/// place the path cannot be followed. A key spelled exactly like `field`
+/// takes precedence, so plain keys containing dots keep working.
+pub(crate) fn resolve<'a>(map: &'a Map<String, Value>, field: &'a str) -> Vec<FieldMatch<'a>> {
+    let path = match FieldPath::parse(field) {
+        Ok(path) if is_path(field) && !map.contains_key(field) => path,
+        _ => {
+            return vec![FieldMatch {
+                path: Cow::Borrowed(field),
+                value: map.get(field),
+            }]
+        }
+    };
+    let mut matches = Vec::new();
+    path.walk_map(map, 0, &mut Vec::new(), &mut matches);
+    matches
+}
+
+/// The first value `field` leads to in `map`, for rules comparing a field
+/// with a single other value.
+pub(crate) fn lookup<'a>(map: &'a Map<String, Value>, field: &'a str) -> Option<&'a Value> {
+    resolve(map, field)
+        .into_iter()
+        .find_map(|found| found.value)
+}
+
+/// The top-level key `field` starts at, so strict mode can tell which row
+/// keys a nested path reads.
+pub(crate) fn root_key(field: &str) -> Option<String> {
+    if !is_path(field) {
+        return Some(field.to_string());
+    }
+    match FieldPath::parse(field).ok()?.steps.into_iter().next()? {
+        Step::Key(key) => Some(key),
+        _ => None,
+    }
+}
+
+/// The position-index segments of a concrete path as reported in
+/// violations, or `None` when it still holds a wildcard. Numeric pointer
+/// tokens are taken as array indexes.
+pub(crate) fn segments(field: &str) -> Option<Vec<PathSegment>> {
+    if !is_path(field) {
+        return Some(vec![PathSegment::Key(field.to_string())]);
+    }
+    let path = FieldPath::parse(field).ok()?;
+    path.steps
+        .into_iter()
+        .map(|step| match step {
+            Step::Key(key) if path.notation == Notation::Pointer => Some(
+                key.parse()
+                    .map_or(PathSegment::Key(key), PathSegment::Index),
+            ),
+            Step::Key(key) => Some(PathSegment::Key(key)),
+            Step::Index(index) => Some(PathSegment::Index(index)),
+            Step::Wildcard => None,
+        })
+        .collect()
+}
+
+impl FieldPath {
+    fn parse(field: &str) -> Result<Self, String> {
+        let (notation, steps) = match field.strip_prefix('/') {
+            Some(pointer) => (Notation::Pointer, parse_pointer(pointer)),
+            None => (Notation::Dotted, parse_dotted(field)?),
+        };
+        Ok(FieldPath { notation, steps })
+    }
+
+    fn walk_map<'a>(
+        &self,
+        map: &'a Map<String, Value>,
+        depth: usize,
+        visited: &mut Vec<Step>,
+        matches: &mut Vec<FieldMatch<'a>>,
+    ) {
+        match &self.steps[depth] {
+            Step::Key(key) => match map.get(key) {
+                Some(value) => self.step(Step::Key(key.clone()), value, depth, visited, matches),
+                None => self.missing(depth, visited, matches),
+            },
+            Step::Wildcard => {
+                for (key, value) in map {
+                    self.step(Step::Key(key.clone()), value, depth, visited, matches);
+                }
+            }
+            Step::Index(_) => self.missing(depth, visited, matches),
+        }
+    }
+
+    fn walk<'a>(
+        &self,
+        value: &'a Value,
+        depth: usize,
+        visited: &mut Vec<Step>,
+        matches: &mut Vec<FieldMatch<'a>>,
+    ) {
+        if depth == self.steps.len() {
+            matches.push(FieldMatch {
+                path: Cow::Owned(self.render(visited, &[])),
+                value: Some(value),
+            });
+            return;
+        }
+        match (value, &self.steps[depth]) {
+            (Value::Object(map), _) => self.walk_map(map, depth, visited, matches),
+            (Value::Array(items), Step::Wildcard) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.step(Step::Index(index), item, depth, visited, matches);
+                }
+            }
+            (Value::Array(items), step) => {
+                let index = match step {
+                    Step::Index(index) => Some(*index),
+                    Step::Key(key) => key.parse().ok(),
+                    Step::Wildcard => None,
+                };
+                match index.and_then(|index| Some((index, items.get(index)?))) {
+                    Some((index, item)) => {
+                        self.step(Step::Index(index), item, depth, visited, matches)
+                    }
+                    None => self.missing(depth, visited, matches),
+                }
+            }
+            _ => self.missing(depth, visited, matches),
+        }
+    }
+
+    fn step<'a>(
+        &self,
+        taken: Step,
+        value: &'a Value,
+        depth: usize,
+        visited: &mut Vec<Step>,
+        matches: &mut Vec<FieldMatch<'a>>,
+    ) {
+        visited.push(taken);
+        self.walk(value, depth + 1, visited, matches);
+        visited.pop();
+    }
+
+    fn missing(&self, depth: usize, visited: &[Step], matches: &mut Vec<FieldMatch>) {
+        matches.push(FieldMatch {
+            path: Cow::Owned(self.render(visited, &self.steps[depth..])),
+            value: None,
+        });
+    }
+
+    fn render(&self, visited: &[Step], rest: &[Step]) -> String {
+        let mut rendered = String::new();
+        for step in visited.iter().chain(rest) {
+            match (self.notation, step) {
+                (Notation::Dotted, Step::Key(key)) => {
+                    if !rendered.is_empty() {
+                        rendered.push('.');
+                    }
+                    rendered.push_str(key);
+                }
+                (Notation::Dotted, Step::Index(index)) => rendered.push_str(&format!("[{index}]")),
+                (Notation::Dotted, Step::Wildcard) => rendered.push_str("[*]"),
+                (Notation::Pointer, Step::Key(key)) => {
+                    rendered.push('/');
+                    rendered.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                }
+                (Notation::Pointer, Step::Index(index)) => rendered.push_str(&format!("/{index}")),
+                (Notation::Pointer, Step::Wildcard) => rendered.push_str("/*"),
+            }
+        }
+        rendered
+    }
+}
+
+// `a.b[0].c[*]`: keys separated by dots, each followed by any number of
+// `[index]` or `[*]` selectors. A bare `*` key is a wildcard too.
+fn parse_dotted(field: &str) -> Result<Vec<Step>, String> {
+    let mut steps = Vec::new();
+    for segment in field.split('.') {
+        let (key, mut selectors) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        match key {
+            "" => return Err(format!("empty key in '{field}'")),
+            "*" => steps.push(Step::Wildcard),
+            key => steps.push(Step::Key(key.to_string())),
+        }
+        while !selectors.is_empty() {
+            let close = selectors
+                .find(']')
+                .filter(|_| selectors.starts_with('['))
+                .ok_or_else(|| format!("malformed index in '{field}'"))?;
+            steps.push(match &selectors[1..close] {
+                "*" => Step::Wildcard,
+                index => Step::Index(
+                    index
+                        .parse()
+                        .map_err(|_| format!("index '{index}' in '{field}' is not a number"))?,
+                ),
+            });
+            selectors = &selectors[close + 1..];
+        }
+    }
+    Ok(steps)
+}
+
+// RFC 6901 tokens, with `*` as a wildcard; there is no escape for a literal
+// `*` key.
+fn parse_pointer(pointer: &str) -> Vec<Step> {
+    pointer
+        .split('/')
+        .map(|token| match token {
+            "*" => Step::Wildcard,
+            token => Step::Key(token.replace("~1", "/").replace("~0", "~")),
+        })
+        .collect()
+}
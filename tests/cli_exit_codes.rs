@@ -1,6 +1,7 @@
 use std::fs;
+use std::io::Write;
 use std::path::Path;
-use std::process::{Command, Output};
+use std::process::{Command, Output, Stdio};
 
 use serde_json::{json, Value};
 use tempfile::tempdir;
@@ -20,6 +21,15 @@ fn run_cli(contract_path: &Path, output_path: &Path) -> Output {
         .expect("run llmc binary")
 }
 
+fn run_cli_batch(contract_path: &Path, output_paths: &[&Path]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_llmc"));
+    command.arg("--contract").arg(contract_path);
+    for output_path in output_paths {
+        command.arg("--output").arg(output_path);
+    }
+    command.output().expect("run llmc binary")
+}
+
 fn assert_exit_code(output: &Output, expected: i32) {
     assert_eq!(
         output.status.code(),
@@ -230,3 +240,1212 @@ fn exits_three_when_output_file_is_missing() {
     assert_exit_code(&result, 3);
     assert_stdout_verdict_schema(&result);
 }
+
+#[test]
+fn batch_exits_one_when_ids_repeat_across_outputs() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let first_path = dir.path().join("first.json");
+    let second_path = dir.path().join("second.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "unique_across_outputs", "field": "id"}
+        ]
+    });
+
+    write_json(&contract_path, &contract);
+    write_json(&first_path, &json!([{"id": 1}, {"id": 2}]));
+    write_json(&second_path, &json!([{"id": 3}, {"id": 1}]));
+
+    let result = run_cli_batch(&contract_path, &[&first_path, &second_path]);
+    assert_exit_code(&result, 1);
+
+    let document: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(document["status"], "fail");
+    let verdicts = document["verdicts"].as_array().expect("verdicts array");
+    assert_eq!(verdicts.len(), 2);
+    assert_eq!(verdicts[0]["status"], "pass");
+    assert_eq!(verdicts[1]["status"], "fail");
+    assert_eq!(
+        verdicts[1]["output"],
+        Value::String(second_path.display().to_string())
+    );
+}
+
+#[test]
+fn batch_verifies_jsonl_records_and_output_dirs_with_a_summary() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let jsonl_path = dir.path().join("results.jsonl");
+    let output_dir = dir.path().join("generations");
+    fs::create_dir(&output_dir).expect("create output dir");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "object",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "number_range", "field": "score", "min": 0, "max": 1}
+        ]
+    });
+
+    write_json(&contract_path, &contract);
+    fs::write(
+        &jsonl_path,
+        "{\"id\": 1, \"score\": 0.5}\n\n{\"score\": 2}\n",
+    )
+    .expect("write jsonl");
+    write_json(&output_dir.join("a.json"), &json!({"id": 2, "score": 0.1}));
+    fs::write(output_dir.join("notes.txt"), "not an output").expect("write notes");
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--outputs")
+        .arg(&jsonl_path)
+        .arg("--output-dir")
+        .arg(&output_dir)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 1);
+
+    let document: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    let outputs: Vec<&str> = document["verdicts"]
+        .as_array()
+        .expect("verdicts array")
+        .iter()
+        .map(|verdict| verdict["output"].as_str().expect("output label"))
+        .collect();
+    assert_eq!(
+        outputs,
+        vec![
+            format!("{}:1", jsonl_path.display()),
+            format!("{}:3", jsonl_path.display()),
+            output_dir.join("a.json").display().to_string(),
+        ]
+    );
+    assert_eq!(
+        document["verdicts"][1]["violations"][0]["position"]["line"],
+        3
+    );
+    assert_eq!(
+        document["summary"],
+        json!({
+            "outputs": 3,
+            "passed": 2,
+            "partial_passed": 0,
+            "partial": 0,
+            "failed": 1,
+            "violations": 2,
+            "violations_by_rule": {"number_range": 1, "required_field": 1}
+        })
+    );
+
+    // An unparseable line counts under `Runtime`, so the totals agree.
+    fs::write(&jsonl_path, "{\"score\": 2}\nnot json\n").expect("write jsonl");
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--outputs")
+        .arg(&jsonl_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 3);
+
+    let document: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(document["summary"]["violations"], 3);
+    assert_eq!(
+        document["summary"]["violations_by_rule"],
+        json!({"Runtime": 1, "number_range": 1, "required_field": 1})
+    );
+
+    // So do strict mode's unknown fields, which no rule checks.
+    fs::write(
+        &jsonl_path,
+        "{\"id\": 1, \"score\": 0.5, \"note\": \"x\"}\n",
+    )
+    .expect("write jsonl");
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--outputs")
+        .arg(&jsonl_path)
+        .arg("--strict")
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 1);
+
+    let document: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(document["summary"]["violations"], 1);
+    assert_eq!(
+        document["summary"]["violations_by_rule"],
+        json!({"UnknownField": 1})
+    );
+}
+
+#[test]
+fn extract_flag_verifies_json_wrapped_in_prose() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("transcript.txt");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "object",
+        "rules": [{"rule": "required_field", "field": "id"}]
+    });
+    write_json(&contract_path, &contract);
+    fs::write(
+        &output_path,
+        "Here is the result: {\"id\": 7}\nHope this helps.",
+    )
+    .expect("write transcript");
+
+    let result = run_cli(&contract_path, &output_path);
+    assert_exit_code(&result, 3);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--output")
+        .arg(&output_path)
+        .arg("--extract")
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 0);
+    let verdict: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(verdict["status"], "pass");
+    assert_eq!(verdict["extraction"]["method"], "embedded");
+    assert_eq!(
+        verdict["extraction"]["note"],
+        "JSON extracted from line 1, column 21; the surrounding text was ignored."
+    );
+}
+
+#[test]
+fn reads_the_output_from_stdin_for_a_dash() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "object",
+        "rules": [{"rule": "required_field", "field": "id"}]
+    });
+    write_json(&contract_path, &contract);
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--output")
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("run llmc binary");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(b"{\"title\": \"no id\"}")
+        .expect("write stdin");
+    let result = child.wait_with_output().expect("wait for llmc");
+
+    assert_exit_code(&result, 1);
+    let verdict: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(verdict["violations"][0]["code"], "LLMC101");
+    assert_eq!(verdict["meta"]["output"], "-");
+}
+
+#[test]
+fn exits_zero_on_partial_pass_with_row_pass_threshold() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"}
+        ]
+    });
+    let output = json!([
+        {"id": 1},
+        {"id": 2},
+        {"name": "Carol"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--output")
+        .arg(&output_path)
+        .arg("--row-pass-threshold")
+        .arg("0.6")
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 0);
+    assert_stdout_verdict_schema(&result);
+
+    let verdict: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(verdict["status"], "partial_pass");
+    assert_eq!(verdict["rows"][2]["status"], "fail");
+}
+
+#[test]
+fn exits_four_on_partial_between_row_thresholds() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+    let failing_path = dir.path().join("failing.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "row_pass_threshold": 0.9,
+        "row_fail_threshold": 0.5,
+        "rules": [
+            {"rule": "required_field", "field": "id"}
+        ]
+    });
+    let output = json!([
+        {"id": 1},
+        {"id": 2},
+        {"name": "Carol"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+    write_json(&failing_path, &json!([{"name": "Dave"}]));
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--output")
+        .arg(&output_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 4);
+    assert_stdout_verdict_schema(&result);
+
+    let verdict: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(verdict["status"], "partial");
+    assert_eq!(verdict["row_fail_threshold"], 0.5);
+
+    // A failing output outranks a partial one in a batch.
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--output")
+        .arg(&output_path)
+        .arg("--output")
+        .arg(&failing_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 1);
+
+    // `--fail-on` applies to partial verdicts as it does to failing ones.
+    let mut contract = contract;
+    contract["rules"][0]["severity"] = json!("warning");
+    write_json(&contract_path, &contract);
+    for (fail_on, expected) in [("error", 0), ("warning", 4)] {
+        let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+            .arg("--contract")
+            .arg(&contract_path)
+            .arg("--output")
+            .arg(&output_path)
+            .arg("--fail-on")
+            .arg(fail_on)
+            .output()
+            .expect("run llmc binary");
+        assert_exit_code(&result, expected);
+    }
+}
+
+#[test]
+fn fail_on_error_ignores_warning_violations() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "regex", "field": "code", "pattern": "^[A-Z]{3}$", "severity": "warning"}
+        ]
+    });
+    let output = json!([
+        {"code": "ab1"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--output")
+        .arg(&output_path)
+        .arg("--fail-on")
+        .arg("error")
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 0);
+
+    let verdict: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(verdict["status"], "fail");
+    assert_eq!(verdict["violations"][0]["severity"], "warning");
+
+    let result = run_cli(&contract_path, &output_path);
+    assert_exit_code(&result, 1);
+}
+
+#[test]
+fn group_by_row_nests_violations_under_rows() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "min_items", "value": 3}
+        ]
+    });
+    let output = json!([
+        {"id": 1},
+        {"name": "Bob"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--output")
+        .arg(&output_path)
+        .arg("--group-by")
+        .arg("row")
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 1);
+    assert_stdout_verdict_schema(&result);
+
+    let verdict: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(verdict["violations"].as_array().map(Vec::len), Some(1));
+    assert_eq!(verdict["violations"][0]["code"], "LLMC401");
+    assert_eq!(verdict["rows"][0]["status"], "pass");
+    assert_eq!(verdict["rows"][1]["status"], "fail");
+    assert_eq!(verdict["rows"][1]["violations"][0]["code"], "LLMC101");
+}
+
+#[test]
+fn verdict_includes_run_metadata() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "contract": "people",
+        "version": 3,
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [{"rule": "required_field", "field": "id"}]
+    });
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &json!([{"id": 1}]));
+
+    let result = run_cli(&contract_path, &output_path);
+    assert_exit_code(&result, 0);
+
+    let verdict: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    let meta = &verdict["meta"];
+    assert_eq!(meta["tool"], "llmc");
+    assert_eq!(meta["tool_version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(meta["contract"]["name"], "people");
+    assert_eq!(meta["contract"]["version"], 3);
+    assert_eq!(meta["contract"]["sha256"].as_str().map(str::len), Some(64));
+    assert_eq!(meta["output"], output_path.display().to_string());
+    assert!(meta["started_at"].is_string());
+    assert!(meta["finished_at"].is_string());
+    assert!(meta["duration_ms"].is_u64());
+}
+
+#[test]
+fn verdict_diff_reports_appeared_disappeared_and_changed_violations() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let old_output_path = dir.path().join("old_output.json");
+    let new_output_path = dir.path().join("new_output.json");
+    let before_path = dir.path().join("before.json");
+    let after_path = dir.path().join("after.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "allowed_values", "field": "status", "values": ["open"]}
+        ]
+    });
+    write_json(&contract_path, &contract);
+    write_json(
+        &old_output_path,
+        &json!([{"status": "closed"}, {"id": 2, "status": "pending"}]),
+    );
+    write_json(
+        &new_output_path,
+        &json!([{"id": 1, "status": "closed"}, {"id": 2, "status": "draft"}, {"status": "open"}]),
+    );
+
+    fs::write(
+        &before_path,
+        run_cli(&contract_path, &old_output_path).stdout,
+    )
+    .expect("save verdict");
+    fs::write(
+        &after_path,
+        run_cli(&contract_path, &new_output_path).stdout,
+    )
+    .expect("save verdict");
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("verdict-diff")
+        .arg(&before_path)
+        .arg(&after_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 1);
+
+    let diff: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(diff["status_before"], "fail");
+    assert_eq!(diff["status_after"], "fail");
+    assert_eq!(diff["unchanged"], 1);
+    assert_eq!(diff["appeared"][0]["code"], "LLMC101");
+    assert_eq!(diff["appeared"][0]["row"], 2);
+    assert_eq!(diff["disappeared"][0]["code"], "LLMC101");
+    assert_eq!(diff["disappeared"][0]["row"], 0);
+    assert_eq!(diff["changed"][0]["before"]["actual"], "pending");
+    assert_eq!(diff["changed"][0]["after"]["actual"], "draft");
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("verdict-diff")
+        .arg(&before_path)
+        .arg(&before_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 0);
+}
+
+#[test]
+fn profile_summarizes_fields_without_a_contract() {
+    let dir = tempdir().expect("create temp dir");
+    let first_path = dir.path().join("first.json");
+    let second_path = dir.path().join("second.json");
+
+    write_json(
+        &first_path,
+        &json!([{"id": 1, "name": "Alice"}, {"id": 2, "name": null}]),
+    );
+    write_json(&second_path, &json!([{"id": "3", "tags": ["a", "b"]}]));
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("profile")
+        .arg(&first_path)
+        .arg(&second_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 0);
+
+    let profile: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(profile["outputs"], 2);
+    assert_eq!(profile["rows"], 3);
+    assert_eq!(
+        profile["fields"]["id"]["types"],
+        json!({"number": 2, "string": 1})
+    );
+    assert_eq!(profile["fields"]["id"]["distinct"], 3);
+    assert_eq!(profile["fields"]["name"]["present"], 2);
+    assert_eq!(profile["fields"]["name"]["null_rate"], 1.0 / 3.0);
+    assert_eq!(
+        profile["fields"]["name"]["length"],
+        json!({"min": 5, "max": 5, "mean": 5.0})
+    );
+    assert_eq!(profile["fields"]["tags"]["missing_rate"], 2.0 / 3.0);
+}
+
+#[test]
+fn explain_failure_groups_violations_by_root_cause() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+    let verdict_path = dir.path().join("verdict.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "price"},
+            {"rule": "allowed_values", "field": "status", "values": ["ok", "accepted"]}
+        ]
+    });
+    write_json(&contract_path, &contract);
+    write_json(
+        &output_path,
+        &json!([
+            {"status": "ok"},
+            {"status": "pending"},
+            {"price": 3, "status": "rejected"},
+            {"status": "pending"}
+        ]),
+    );
+    fs::write(&verdict_path, run_cli(&contract_path, &output_path).stdout).expect("save verdict");
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("explain-failure")
+        .arg(&verdict_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 0);
+    assert_eq!(
+        String::from_utf8_lossy(&result.stdout),
+        "FAIL: 6 violations in 2 root causes.\n\
+         - 'price' is missing in 3 rows.\n\
+         - 'status' uses values outside {ok, accepted} in 3 rows (seen: pending, rejected).\n"
+    );
+}
+
+#[test]
+fn remediate_renders_prompt_template_from_verdict() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+    let verdict_path = dir.path().join("verdict.json");
+    let template_path = dir.path().join("prompt.j2");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "price"},
+            {"rule": "datetime_format", "field": "day", "preset": "iso_date"}
+        ]
+    });
+    write_json(&contract_path, &contract);
+    write_json(
+        &output_path,
+        &json!([{"price": 1, "day": "2024-01-01"}, {"day": "01/02/2024"}]),
+    );
+    fs::write(&verdict_path, run_cli(&contract_path, &output_path).stdout).expect("save verdict");
+    fs::write(
+        &template_path,
+        "Fix rows {{ failed_rows | join(\", \") }} ({{ fields | join(\", \") }}):\n\
+         {% for v in violations %}- {{ v.field }}: {{ v.hint }}\n{% endfor %}",
+    )
+    .expect("write template");
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("remediate")
+        .arg("--template")
+        .arg(&template_path)
+        .arg(&verdict_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 0);
+    assert_eq!(
+        String::from_utf8_lossy(&result.stdout),
+        "Fix rows 1 (day, price):\n\
+         - price: Include field 'price'.\n\
+         - day: Field 'day' must be a datetime string in format iso_date.\n"
+    );
+
+    fs::write(&template_path, "{% for v in violations %}").expect("write template");
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("remediate")
+        .arg("--template")
+        .arg(&template_path)
+        .arg(&verdict_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 3);
+    assert_stdout_verdict_schema(&result);
+}
+
+#[test]
+fn batch_writes_metrics_and_badge() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let metrics_path = dir.path().join("metrics.json");
+    let badge_path = dir.path().join("badge.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "min_items", "value": 1}
+        ]
+    });
+    write_json(&contract_path, &contract);
+
+    let outputs = [
+        json!([{"id": 1}]),
+        json!([{"id": 2}, {"id": 3}]),
+        json!([{"id": 4}, {"name": "x"}, {"name": "y"}]),
+        json!([{"id": 5}]),
+    ];
+    let output_paths: Vec<_> = outputs
+        .iter()
+        .enumerate()
+        .map(|(idx, output)| {
+            let path = dir.path().join(format!("output_{idx}.json"));
+            write_json(&path, output);
+            path
+        })
+        .collect();
+
+    let mut command = Command::new(env!("CARGO_BIN_EXE_llmc"));
+    command.arg("--contract").arg(&contract_path);
+    for path in &output_paths {
+        command.arg("--output").arg(path);
+    }
+    let result = command
+        .arg("--metrics")
+        .arg(&metrics_path)
+        .arg("--badge")
+        .arg(&badge_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 1);
+
+    let metrics: Value =
+        serde_json::from_str(&fs::read_to_string(&metrics_path).expect("read metrics"))
+            .expect("metrics are valid json");
+    assert_eq!(metrics["schema_version"], 1);
+    assert_eq!(metrics["outputs"], 4);
+    assert_eq!(metrics["passed"], 3);
+    assert_eq!(metrics["failed"], 1);
+    assert_eq!(metrics["pass_rate"], 0.75);
+    assert_eq!(metrics["violations"], 2);
+    assert_eq!(
+        metrics["rules"],
+        json!([
+            {"rule": "required_field", "field": "id", "violations": 2, "failed_outputs": 1},
+            {"rule": "min_items", "violations": 0, "failed_outputs": 0}
+        ])
+    );
+
+    let badge: Value = serde_json::from_str(&fs::read_to_string(&badge_path).expect("read badge"))
+        .expect("badge is valid json");
+    assert_eq!(
+        badge,
+        json!({"schemaVersion": 1, "label": "contract", "message": "75% pass", "color": "yellow"})
+    );
+}
+
+#[test]
+fn coverage_reports_unviolated_rules_and_unguarded_fields() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let outputs_dir = dir.path().join("outputs");
+    fs::create_dir(&outputs_dir).expect("create outputs dir");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "allowed_values", "field": "status", "values": ["open"]}
+        ]
+    });
+    write_json(&contract_path, &contract);
+    write_json(
+        &outputs_dir.join("a.json"),
+        &json!([{"id": 1, "status": "open", "note": "x"}]),
+    );
+    write_json(
+        &outputs_dir.join("b.json"),
+        &json!([{"status": "open", "note": "y"}]),
+    );
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("coverage")
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--outputs")
+        .arg(outputs_dir.join("*.json"))
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 0);
+
+    let report: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(report["outputs"], 2);
+    assert_eq!(report["rules"][0]["failed_outputs"], 1);
+    assert_eq!(
+        report["never_violated"],
+        json!([{"rule": "allowed_values", "field": "status", "violations": 0, "failed_outputs": 0}])
+    );
+    assert_eq!(
+        report["unguarded_fields"],
+        json!([{"field": "note", "present": 2}])
+    );
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("coverage")
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--outputs")
+        .arg(outputs_dir.join("*.txt"))
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 3);
+}
+
+#[test]
+fn mutate_breaks_each_rule_of_a_passing_output() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+    let mutants_dir = dir.path().join("mutants");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "field_type", "field": "id", "expected": "number"},
+            {"rule": "allowed_values", "field": "status", "values": ["open", "closed"]},
+            {"rule": "min_items", "value": 2}
+        ]
+    });
+    write_json(&contract_path, &contract);
+    write_json(
+        &output_path,
+        &json!([{"id": 1, "status": "open"}, {"id": 2, "status": "closed"}]),
+    );
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("mutate")
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--from")
+        .arg(&output_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 0);
+
+    let report: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    let mutants = report["mutants"].as_array().expect("mutants array");
+    let summary: Vec<(&str, &str, bool)> = mutants
+        .iter()
+        .map(|mutant| {
+            (
+                mutant["name"].as_str().unwrap(),
+                mutant["code"].as_str().unwrap(),
+                mutant["triggered"].as_bool().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("required_field-id", "LLMC101", true),
+            ("field_type-id", "LLMC201", true),
+            ("allowed_values-status", "LLMC301", true),
+            ("min_items", "LLMC401", true),
+        ]
+    );
+    assert_eq!(
+        mutants[0]["output"],
+        json!([{"status": "open"}, {"id": 2, "status": "closed"}])
+    );
+    assert_eq!(mutants[3]["output"], json!([{"id": 1, "status": "open"}]));
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("mutate")
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--from")
+        .arg(&output_path)
+        .arg("--out-dir")
+        .arg(&mutants_dir)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 0);
+    let written: Value = serde_json::from_str(
+        &fs::read_to_string(mutants_dir.join("field_type-id.json")).expect("read mutant"),
+    )
+    .expect("mutant is valid json");
+    assert_eq!(written[0]["id"], "__llmc_mutant__");
+    let result = run_cli(
+        &contract_path,
+        &mutants_dir.join("allowed_values-status.json"),
+    );
+    assert_exit_code(&result, 1);
+
+    write_json(&output_path, &json!([{"id": 1, "status": "open"}]));
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("mutate")
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--from")
+        .arg(&output_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 1);
+    assert_stdout_verdict_schema(&result);
+}
+
+#[test]
+fn generate_produces_outputs_that_satisfy_the_contract() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let outputs_dir = dir.path().join("generated");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "regex", "field": "id", "pattern": "^[A-Z]{3}-[0-9]{4}$"},
+            {"rule": "field_type", "field": "score", "expected": "number"},
+            {"rule": "allowed_values", "field": "status", "values": ["open", "closed"]},
+            {"rule": "field_array_length", "field": "tags", "min": 1, "max": 3},
+            {"rule": "min_items", "value": 3}
+        ]
+    });
+    write_json(&contract_path, &contract);
+
+    let generate = |seed: &str| {
+        Command::new(env!("CARGO_BIN_EXE_llmc"))
+            .arg("generate")
+            .arg("--contract")
+            .arg(&contract_path)
+            .arg("--count")
+            .arg("5")
+            .arg("--seed")
+            .arg(seed)
+            .output()
+            .expect("run llmc binary")
+    };
+    let result = generate("7");
+    assert_exit_code(&result, 0);
+    assert_eq!(result.stdout, generate("7").stdout);
+
+    let report: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    let outputs = report["outputs"].as_array().expect("outputs array");
+    assert_eq!(outputs.len(), 5);
+    for generated in outputs {
+        assert_eq!(generated["status"], "pass");
+        let rows = generated["output"].as_array().expect("array output");
+        assert!(rows.len() >= 3);
+        assert!(rows[0]["score"].is_number());
+    }
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("generate")
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--count")
+        .arg("2")
+        .arg("--out-dir")
+        .arg(&outputs_dir)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 0);
+    let result = run_cli(&contract_path, &outputs_dir.join("2.json"));
+    assert_exit_code(&result, 0);
+}
+
+#[test]
+#[cfg(feature = "tui")]
+fn tui_requires_a_terminal_and_a_readable_verdict() {
+    let dir = tempdir().expect("create temp dir");
+    let verdict_path = dir.path().join("verdict.json");
+    write_json(&verdict_path, &json!({"status": "pass", "violations": []}));
+
+    for path in [verdict_path, dir.path().join("missing.json")] {
+        let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+            .arg("tui")
+            .arg(&path)
+            .output()
+            .expect("run llmc binary");
+        assert_exit_code(&result, 3);
+        assert_stdout_verdict_schema(&result);
+    }
+
+    // A batch document without verdicts has nothing to browse.
+    let batch_path = dir.path().join("batch.json");
+    write_json(&batch_path, &json!({"verdicts": []}));
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("tui")
+        .arg(&batch_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 3);
+    let verdict: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert!(verdict["violations"][0]["message"]
+        .as_str()
+        .is_some_and(|message| message.ends_with("holds no verdicts to browse.")));
+}
+
+#[test]
+fn sarif_and_junit_formats_write_ci_reports_to_out() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let passing_path = dir.path().join("passing.json");
+    let failing_path = dir.path().join("failing.json");
+    let report_path = dir.path().join("report");
+
+    let contract = json!({
+        "contract": "tickets",
+        "version": 3,
+        "inputs": ["prompt"],
+        "output_type": "object",
+        "rules": [
+            {"rule": "allowed_values", "field": "status", "values": ["open", "closed"]}
+        ]
+    });
+    write_json(&contract_path, &contract);
+    write_json(&passing_path, &json!({"status": "open"}));
+    fs::write(&failing_path, "{\n  \"status\": \"<stale>\"\n}").expect("write output");
+
+    let run_format = |format: &str| {
+        Command::new(env!("CARGO_BIN_EXE_llmc"))
+            .arg("--contract")
+            .arg(&contract_path)
+            .arg("--output")
+            .arg(&passing_path)
+            .arg("--output")
+            .arg(&failing_path)
+            .arg("--format")
+            .arg(format)
+            .arg("--out")
+            .arg(&report_path)
+            .output()
+            .expect("run llmc binary")
+    };
+
+    let result = run_format("sarif");
+    assert_exit_code(&result, 1);
+    assert!(result.stdout.is_empty());
+    let log: Value = serde_json::from_str(&fs::read_to_string(&report_path).expect("read report"))
+        .expect("report is valid json");
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["automationDetails"]["id"], "tickets@3");
+    assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "LLMC301");
+    let results = run["results"].as_array().expect("results array");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ruleId"], "LLMC301");
+    assert_eq!(results[0]["level"], "error");
+    let location = &results[0]["locations"][0]["physicalLocation"];
+    assert!(location["artifactLocation"]["uri"]
+        .as_str()
+        .expect("uri")
+        .ends_with("/failing.json"));
+    assert_eq!(
+        location["region"],
+        json!({"startLine": 2, "startColumn": 13, "endLine": 2, "endColumn": 22})
+    );
+    assert_eq!(results[0]["properties"]["actual"], "<stale>");
+
+    let result = run_format("junit");
+    assert_exit_code(&result, 1);
+    let xml = fs::read_to_string(&report_path).expect("read report");
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+    assert!(xml.contains("<testsuite name=\"tickets@3\" tests=\"2\" failures=\"1\" errors=\"0\""));
+    assert!(xml.contains(&format!(
+        "<testcase classname=\"tickets@3\" name=\"{}\"/>",
+        passing_path.display()
+    )));
+    assert!(xml.contains("<failure type=\"LLMC301\""));
+    assert!(xml.contains("&quot;&lt;stale&gt;&quot;"));
+}
+
+#[test]
+fn lsp_diagnostics_format_reports_ranges_per_output_file() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("out put.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "allowed_values", "field": "status", "values": ["ok"], "severity": "warning"},
+            {"rule": "min_items", "value": 3}
+        ]
+    });
+    write_json(&contract_path, &contract);
+    fs::write(
+        &output_path,
+        "[\n  {\"name\": \"😀\", \"status\": \"bad\"}\n]\n",
+    )
+    .expect("write output");
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--output")
+        .arg(&output_path)
+        .arg("--format")
+        .arg("lsp-diagnostics")
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 1);
+
+    let params: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    let params = params.as_array().expect("array of params");
+    assert_eq!(params.len(), 1);
+    let uri = params[0]["uri"].as_str().expect("uri");
+    assert!(uri.starts_with("file:///"), "{uri}");
+    assert!(uri.ends_with("/out%20put.json"), "{uri}");
+
+    let diagnostics = params[0]["diagnostics"].as_array().expect("diagnostics");
+    assert_eq!(
+        diagnostics[0]["range"],
+        json!({"start": {"line": 1, "character": 27}, "end": {"line": 1, "character": 32}})
+    );
+    assert_eq!(diagnostics[0]["severity"], 2);
+    assert_eq!(diagnostics[0]["code"], "LLMC301");
+    assert_eq!(diagnostics[0]["source"], "llmc");
+    assert_eq!(diagnostics[0]["data"]["row"], 0);
+    assert_eq!(diagnostics[1]["code"], "LLMC401");
+    assert_eq!(
+        diagnostics[1]["range"],
+        json!({"start": {"line": 0, "character": 0}, "end": {"line": 2, "character": 1}})
+    );
+
+    // The records of a JSONL file share one entry, or clients would keep
+    // only the last record's diagnostics.
+    let jsonl_path = dir.path().join("two.jsonl");
+    fs::write(
+        &jsonl_path,
+        "[{\"status\": \"bad\"}]\n[{\"status\": \"worse\"}]\n",
+    )
+    .expect("write jsonl");
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--outputs")
+        .arg(&jsonl_path)
+        .arg("--format")
+        .arg("lsp-diagnostics")
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 1);
+
+    let params: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    let params = params.as_array().expect("array of params");
+    assert_eq!(params.len(), 1);
+    let lines: Vec<&Value> = params[0]["diagnostics"]
+        .as_array()
+        .expect("diagnostics")
+        .iter()
+        .filter(|diagnostic| diagnostic["code"] == "LLMC301")
+        .map(|diagnostic| &diagnostic["range"]["start"]["line"])
+        .collect();
+    assert_eq!(lines, [&json!(0), &json!(1)]);
+}
+
+#[test]
+fn contract_name_selects_from_a_contract_pack() {
+    let dir = tempdir().expect("create temp dir");
+    let pack_path = dir.path().join("pack.json");
+    let output_path = dir.path().join("output.json");
+
+    write_json(
+        &pack_path,
+        &json!({
+            "version": 2,
+            "contracts": {
+                "triage": {
+                    "inputs": ["ticket"],
+                    "output_type": "object",
+                    "rules": [{"rule": "required_field", "field": "priority"}]
+                }
+            }
+        }),
+    );
+    write_json(&output_path, &json!({"priority": "high"}));
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&pack_path)
+        .arg("--contract-name")
+        .arg("triage")
+        .arg("--output")
+        .arg(&output_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 0);
+    let verdict: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(verdict["meta"]["contract"]["name"], "triage");
+    assert_eq!(verdict["meta"]["contract"]["version"], 2);
+
+    let result = run_cli(&pack_path, &output_path);
+    assert_exit_code(&result, 2);
+    assert_stdout_verdict_schema(&result);
+}
+
+#[test]
+fn meta_flags_record_run_context_in_verdict_and_metrics() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+    let metrics_path = dir.path().join("metrics.json");
+
+    write_json(
+        &contract_path,
+        &json!({
+            "inputs": ["prompt"],
+            "output_type": "object",
+            "rules": [{"rule": "required_field", "field": "id"}]
+        }),
+    );
+    write_json(&output_path, &json!({"id": 1}));
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--output")
+        .arg(&output_path)
+        .args(["--meta", "model=gpt-4o", "--meta", "temperature=0.2"])
+        .args(["--meta", "prompt=a=b"])
+        .arg("--metrics")
+        .arg(&metrics_path)
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 0);
+
+    let context = json!({"model": "gpt-4o", "prompt": "a=b", "temperature": "0.2"});
+    let verdict: Value = serde_json::from_slice(&result.stdout).expect("stdout is valid json");
+    assert_eq!(verdict["meta"]["context"], context);
+    let metrics: Value =
+        serde_json::from_str(&fs::read_to_string(&metrics_path).expect("read metrics"))
+            .expect("metrics is valid json");
+    assert_eq!(metrics["context"], context);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_llmc"))
+        .arg("--contract")
+        .arg(&contract_path)
+        .arg("--output")
+        .arg(&output_path)
+        .args(["--meta", "no-separator"])
+        .output()
+        .expect("run llmc binary");
+    assert_exit_code(&result, 2);
+}
@@ -1,21 +1,24 @@
-#[path = "../src/contract.rs"]
-mod contract;
-#[path = "../src/verifier.rs"]
-mod verifier;
-
 use std::fs;
 use std::path::Path;
 
 use serde_json::{json, Value};
 use tempfile::tempdir;
 
-use verifier::{run, RunError, VerdictStatus};
+use llmc::extract::{extract_json, ExtractionMethod};
+use llmc::verifier::{
+    verify_batch, ExcerptOptions, RunError, Verdict, VerdictStatus, VerifyOptions,
+};
+use llmc::{contract, verifier};
 
 fn write_json(path: &Path, value: &Value) {
     let payload = serde_json::to_string_pretty(value).expect("serialize json fixture");
     fs::write(path, payload).expect("write json fixture");
 }
 
+fn run(contract_path: &Path, output_path: &Path) -> Result<Verdict, RunError> {
+    verifier::run(contract_path, output_path, &VerifyOptions::default())
+}
+
 #[test]
 fn validates_contract_successfully() {
     let dir = tempdir().expect("create temp dir");
@@ -144,3 +147,2108 @@ fn returns_invalid_output_error_for_bad_output_json() {
     let err = run(&contract_path, &output_path).expect_err("output should be invalid json");
     assert!(matches!(err, RunError::InvalidOutput(_)));
 }
+
+#[test]
+fn any_of_passes_when_each_row_matches_one_group() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {
+                "rule": "any_of",
+                "groups": [
+                    [
+                        {"rule": "required_field", "field": "url"},
+                        {"rule": "regex", "field": "url", "pattern": "^https://"}
+                    ],
+                    [
+                        {"rule": "required_field", "field": "file_path"},
+                        {"rule": "field_type", "field": "file_path", "expected": "string"}
+                    ]
+                ]
+            }
+        ]
+    });
+
+    let output = json!([
+        {"url": "https://example.com"},
+        {"file_path": "/tmp/report.pdf"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Pass);
+    assert!(verdict.violations.is_empty());
+}
+
+#[test]
+fn one_of_reports_rows_matching_zero_or_several_groups() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {
+                "rule": "one_of",
+                "groups": [
+                    [{"rule": "required_field", "field": "url"}],
+                    [{"rule": "required_field", "field": "file_path"}]
+                ]
+            }
+        ]
+    });
+
+    let output = json!([
+        {"url": "https://example.com"},
+        {"url": "https://example.com", "file_path": "/tmp/report.pdf"},
+        {"name": "neither"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    let details: Vec<&str> = verdict
+        .violations
+        .iter()
+        .filter(|v| v.rule_name == "OneOf")
+        .map(|v| v.detail.as_str())
+        .collect();
+    assert_eq!(details.len(), 2);
+    assert!(details[0].starts_with("Row 1 matches 2 one_of groups"));
+    assert!(details[1].starts_with("Row 2 matches none of the one_of groups"));
+}
+
+#[test]
+fn returns_invalid_contract_rule_error_for_empty_combinator() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "object",
+        "rules": [
+            {"rule": "any_of", "groups": []}
+        ]
+    });
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &json!({}));
+
+    let err = run(&contract_path, &output_path).expect_err("contract should be invalid");
+    assert!(matches!(err, RunError::InvalidContractRule(_)));
+}
+
+#[test]
+fn not_reports_rows_matching_the_negated_rules() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {
+                "rule": "not",
+                "rules": [
+                    {"rule": "regex", "field": "email", "pattern": "@example\\.com$"}
+                ]
+            }
+        ]
+    });
+
+    // A row without the field does not match the negated rule.
+    let output = json!([
+        {"email": "alice@company.org"},
+        {"email": "bob@example.com"},
+        {"name": "Carol"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    assert_eq!(verdict.violations.len(), 1);
+    assert_eq!(verdict.violations[0].rule_name, "Not");
+    assert_eq!(
+        verdict.violations[0].detail,
+        "Row 1 matches the rules negated by not."
+    );
+}
+
+#[test]
+fn scoring_mode_passes_when_weighted_score_meets_threshold() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "scoring": {"pass_threshold": 0.7},
+        "rules": [
+            {"rule": "required_field", "field": "id", "weight": 3},
+            {"rule": "field_type", "field": "id", "expected": "number", "weight": 1},
+            {"rule": "regex", "field": "code", "pattern": "^[A-Z]{3}$", "weight": 1}
+        ]
+    });
+
+    let output = json!([
+        {"id": 1, "code": "abc"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Pass);
+    assert_eq!(verdict.score, Some(0.8));
+    assert_eq!(verdict.pass_threshold, Some(0.7));
+    assert_eq!(verdict.violations.len(), 1);
+}
+
+#[test]
+fn scoring_mode_fails_below_threshold() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "scoring": {"pass_threshold": 0.9},
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "required_field", "field": "name"}
+        ]
+    });
+
+    let output = json!([
+        {"id": 1}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    assert_eq!(verdict.score, Some(0.5));
+}
+
+#[test]
+fn rule_groups_are_summarized_in_the_verdict() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"}
+        ],
+        "groups": [
+            {
+                "name": "formatting",
+                "description": "Codes are upper-case",
+                "rules": [
+                    {"rule": "regex", "field": "code", "pattern": "^[A-Z]{3}$"}
+                ]
+            },
+            {
+                "name": "contact",
+                "mode": "any",
+                "severity": "warning",
+                "rules": [
+                    {"rule": "required_field", "field": "email"},
+                    {"rule": "required_field", "field": "phone"}
+                ]
+            }
+        ]
+    });
+
+    let output = json!([
+        {"id": 1, "code": "abc", "phone": "+15550100"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    assert_eq!(verdict.violations.len(), 1);
+    assert_eq!(verdict.violations[0].group.as_deref(), Some("formatting"));
+
+    assert_eq!(verdict.groups.len(), 2);
+    assert_eq!(verdict.groups[0].name, "formatting");
+    assert_eq!(verdict.groups[0].status, VerdictStatus::Fail);
+    assert_eq!(verdict.groups[0].violations, 1);
+    assert_eq!(verdict.groups[1].name, "contact");
+    assert_eq!(verdict.groups[1].status, VerdictStatus::Pass);
+}
+
+#[test]
+fn warn_only_rule_violations_do_not_fail_the_verdict() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "regex", "field": "code", "pattern": "^[A-Z]{3}$", "enforce": false}
+        ]
+    });
+
+    let output = json!([
+        {"id": 1, "code": "abc"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Pass);
+    assert_eq!(verdict.violations.len(), 1);
+    assert_eq!(verdict.violations[0].rule_name, "Regex");
+    assert!(!verdict.violations[0].enforced);
+}
+
+#[test]
+fn allowed_values_are_loaded_from_values_file() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    fs::write(
+        dir.path().join("countries.csv"),
+        "DE,Germany\nFR,France\n\nIT,Italy\n",
+    )
+    .expect("write values file");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "allowed_values", "field": "country", "values_file": "countries.csv"}
+        ]
+    });
+
+    let output = json!([
+        {"country": "FR"},
+        {"country": "ES"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    assert_eq!(verdict.violations.len(), 1);
+    assert_eq!(verdict.violations[0].actual, Some(json!("ES")));
+    assert_eq!(
+        verdict.violations[0].expected,
+        Some(json!({"values_file": "countries.csv", "count": 3}))
+    );
+}
+
+#[test]
+fn csv_values_files_parse_quoted_fields_and_skip_a_header() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    fs::write(
+        dir.path().join("countries.csv"),
+        "country,code\r\n\"Korea, Republic of\",KR\r\n\"The \"\"Other\"\" Place\",OP\r\n\"Two\nLines\",TL\r\nFrance,FR\r\n",
+    )
+    .expect("write values file");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {
+                "rule": "allowed_values",
+                "field": "country",
+                "values_file": "countries.csv",
+                "values_file_header": true
+            }
+        ]
+    });
+    let output = json!([
+        {"country": "Korea, Republic of"},
+        {"country": "The \"Other\" Place"},
+        {"country": "Two\nLines"},
+        {"country": "France"},
+        {"country": "Korea"},
+        {"country": "country"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    let rows: Vec<Option<usize>> = verdict.violations.iter().map(|v| v.row).collect();
+    assert_eq!(rows, vec![Some(4), Some(5)]);
+    assert_eq!(
+        verdict.violations[0].expected,
+        Some(json!({"values_file": "countries.csv", "count": 4}))
+    );
+
+    // Without `values_file_header` the header row is a value like any other.
+    let mut contract = contract;
+    contract["rules"][0]
+        .as_object_mut()
+        .expect("rule object")
+        .remove("values_file_header");
+    write_json(&contract_path, &contract);
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+    let rows: Vec<Option<usize>> = verdict.violations.iter().map(|v| v.row).collect();
+    assert_eq!(rows, vec![Some(4)]);
+}
+
+#[test]
+fn returns_invalid_contract_rule_error_for_missing_values_file() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "allowed_values", "field": "country", "values_file": "missing.txt"}
+        ]
+    });
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &json!([]));
+
+    let err = run(&contract_path, &output_path).expect_err("contract should be invalid");
+    assert!(matches!(err, RunError::InvalidContractRule(_)));
+}
+
+#[test]
+fn regex_rules_resolve_builtin_and_contract_patterns() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "patterns": {"ticket": "^[A-Z]{3}-[0-9]{4}$"},
+        "rules": [
+            {"rule": "regex", "field": "email", "pattern_ref": "email"},
+            {"rule": "regex", "field": "ticket", "pattern_ref": "ticket"}
+        ]
+    });
+
+    let output = json!([
+        {"email": "alice@example.com", "ticket": "ABC-1234"},
+        {"email": "not-an-email", "ticket": "abc-12"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    assert_eq!(verdict.violations.len(), 2);
+    assert_eq!(
+        verdict.violations[1].expected,
+        Some(json!("^[A-Z]{3}-[0-9]{4}$"))
+    );
+}
+
+#[test]
+fn returns_invalid_contract_rule_error_for_unknown_pattern_ref() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "regex", "field": "code", "pattern_ref": "does_not_exist"}
+        ]
+    });
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &json!([]));
+
+    let err = run(&contract_path, &output_path).expect_err("contract should be invalid");
+    assert!(matches!(err, RunError::InvalidContractRule(_)));
+}
+
+#[test]
+fn approx_equal_accepts_values_within_tolerance() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "approx_equal", "field": "total", "other_field": "expected_total", "abs_tolerance": 0.01},
+            {"rule": "approx_equal", "field": "ratio", "target": 0.5, "rel_tolerance": 0.1}
+        ]
+    });
+
+    let output = json!([
+        {"total": 10.004, "expected_total": 10.0, "ratio": 0.52},
+        {"total": 10.5, "expected_total": 10.0, "ratio": 0.6}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    let details: Vec<&str> = verdict
+        .violations
+        .iter()
+        .map(|v| v.detail.as_str())
+        .collect();
+    assert_eq!(
+        details,
+        vec![
+            "Row 1 field 'total' is not approximately equal to field 'expected_total' (10).",
+            "Row 1 field 'ratio' is not approximately equal to 0.5."
+        ]
+    );
+}
+
+#[test]
+fn datetime_format_rejects_impossible_dates_with_parse_detail() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "datetime_format", "field": "due", "format": "%Y-%m-%d"},
+            {"rule": "datetime_format", "field": "created_at", "preset": "rfc3339"}
+        ]
+    });
+
+    let output = json!([
+        {"due": "2024-02-29", "created_at": "2024-02-29T10:00:00+01:00"},
+        {"due": "2023-02-30", "created_at": "2024-02-29 10:00"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    assert_eq!(verdict.violations.len(), 2);
+    assert_eq!(
+        verdict.violations[0].detail,
+        "Row 1 field 'due' does not match datetime format '%Y-%m-%d': input is out of range."
+    );
+    assert_eq!(verdict.violations[1].expected, Some(json!("rfc3339")));
+}
+
+#[test]
+fn returns_invalid_contract_rule_error_for_bad_datetime_format() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "datetime_format", "field": "due", "format": "%Y-%Q"}
+        ]
+    });
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &json!([]));
+
+    let err = run(&contract_path, &output_path).expect_err("contract should be invalid");
+    assert!(matches!(err, RunError::InvalidContractRule(_)));
+}
+
+#[test]
+fn datetime_order_compares_instants_across_timezones() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "datetime_order", "field": "starts_at", "other_field": "ends_at"}
+        ]
+    });
+
+    let output = json!([
+        {"starts_at": "2024-05-01T10:00:00+02:00", "ends_at": "2024-05-01T09:30:00Z"},
+        {"starts_at": "2024-05-01T10:00:00Z", "ends_at": "2024-05-01T11:00:00+02:00"},
+        {"starts_at": "tomorrow", "ends_at": "2024-05-01T11:00:00Z"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    let details: Vec<&str> = verdict
+        .violations
+        .iter()
+        .map(|v| v.detail.as_str())
+        .collect();
+    assert_eq!(details.len(), 2);
+    assert_eq!(
+        details[0],
+        "Row 1 field 'starts_at' must be before field 'ends_at'."
+    );
+    assert!(details[1].starts_with("Row 2 field 'starts_at' is not a valid datetime"));
+}
+
+#[test]
+fn locale_number_parses_localized_strings_and_checks_bounds() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "locale_number", "field": "price", "locale": "de", "min": 0, "max": 5000}
+        ]
+    });
+
+    let output = json!([
+        {"price": "1.234,56"},
+        {"price": "1,234.56"},
+        {"price": "12.345,00"},
+        {"price": 12}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    let details: Vec<&str> = verdict
+        .violations
+        .iter()
+        .map(|v| v.detail.as_str())
+        .collect();
+    assert_eq!(
+        details,
+        vec![
+            "Row 1 field 'price' is not a number string in locale 'de'.",
+            "Row 2 field 'price' value 12345 must be at most 5000.",
+            "Row 3 field 'price' must be a string for locale_number rule."
+        ]
+    );
+    let codes: Vec<&str> = verdict.violations.iter().map(|v| v.code).collect();
+    assert_eq!(codes, vec!["LLMC309", "LLMC310", "LLMC202"]);
+}
+
+#[test]
+fn every_rule_has_its_own_default_code() {
+    // Serde lists every rule name when rejecting an unknown one.
+    let err = serde_json::from_value::<contract::Rule>(json!({"rule": "?"}))
+        .expect_err("unknown rule")
+        .to_string();
+    let names = err
+        .split("expected one of ")
+        .nth(1)
+        .expect("list of rule names");
+    let names: Vec<&str> = names
+        .split(", ")
+        .map(|name| name.trim_matches(|c: char| c == '`' || c.is_whitespace()))
+        .collect();
+    assert!(names.contains(&"required_field") && names.contains(&"not"));
+    for name in names {
+        // Violations are reported under the rule's name in CamelCase.
+        let rule_name: String = name
+            .split('_')
+            .map(|word| word[..1].to_uppercase() + &word[1..])
+            .collect();
+        assert_ne!(
+            llmc::codes::default_code(&rule_name),
+            llmc::codes::RUNTIME_ERROR,
+            "{rule_name}"
+        );
+    }
+}
+
+#[test]
+fn normalization_options_relax_value_comparisons() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let normalize =
+        json!({"case_fold": true, "strip_diacritics": true, "collapse_whitespace": true});
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "allowed_values", "field": "venue", "values": ["cafe", "bar"], "normalize": normalize},
+            {"rule": "const", "field": "city", "value": "São Paulo", "normalize": normalize},
+            {"rule": "const", "field": "kind", "value": "venue"}
+        ]
+    });
+
+    let output = json!([
+        {"venue": "Café", "city": "  sao   PAULO ", "kind": "venue"},
+        {"venue": "Pub", "city": "Rio", "kind": "Venue"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    let details: Vec<&str> = verdict
+        .violations
+        .iter()
+        .map(|v| v.detail.as_str())
+        .collect();
+    assert_eq!(
+        details,
+        vec![
+            "Row 1 field 'venue' has a disallowed value.",
+            "Row 1 field 'city' must equal \"São Paulo\".",
+            "Row 1 field 'kind' must equal \"venue\"."
+        ]
+    );
+}
+
+#[test]
+fn unique_across_outputs_reports_duplicates_in_later_outputs() {
+    let contract: contract::Contract = serde_json::from_value(json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "unique_across_outputs", "field": "id"}
+        ]
+    }))
+    .expect("parse contract");
+
+    let first = json!([{"id": "a1"}, {"id": "a2"}]);
+    let second = json!([{"id": "b1"}, {"id": "a2"}]);
+
+    let verdicts = verify_batch(
+        &contract,
+        &[("first.json", &first), ("second.json", &second)],
+    )
+    .expect("valid contract");
+
+    assert_eq!(verdicts.len(), 2);
+    assert_eq!(verdicts[0].status, VerdictStatus::Pass);
+    assert_eq!(verdicts[1].status, VerdictStatus::Fail);
+    assert_eq!(
+        verdicts[1].violations[0].detail,
+        "Row 1 field 'id' value \"a2\" duplicates row 1 of output 'first.json'."
+    );
+}
+
+#[test]
+fn consistent_across_outputs_reports_conflicting_values_for_a_key() {
+    let contract: contract::Contract = serde_json::from_value(json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "consistent_across_outputs", "key": "product_id", "fields": ["category"]}
+        ]
+    }))
+    .expect("parse contract");
+
+    let first = json!([{"product_id": "p1", "category": "books"}]);
+    let second = json!([
+        {"product_id": "p2", "category": "toys"},
+        {"product_id": "p1", "category": "toys"}
+    ]);
+
+    let verdicts = verify_batch(
+        &contract,
+        &[("first.json", &first), ("second.json", &second)],
+    )
+    .expect("valid contract");
+
+    assert_eq!(verdicts[0].status, VerdictStatus::Pass);
+    assert_eq!(verdicts[1].status, VerdictStatus::Fail);
+    assert_eq!(verdicts[1].violations.len(), 1);
+    assert_eq!(
+        verdicts[1].violations[0].detail,
+        "Row 1 field 'category' value \"toys\" differs from \"books\" at row 0 of output 'first.json' for 'product_id' \"p1\"."
+    );
+}
+
+#[test]
+fn required_field_default_is_suggested_in_violation() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "status", "default": "pending"}
+        ]
+    });
+
+    let output = json!([
+        {"status": "done"},
+        {"id": 2}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    assert_eq!(verdict.violations.len(), 1);
+    let violation = &verdict.violations[0];
+    assert_eq!(violation.expected, Some(json!("pending")));
+    assert_eq!(
+        violation.suggestion.as_deref(),
+        Some("Set 'status' to \"pending\".")
+    );
+}
+
+#[test]
+fn field_array_length_checks_each_row() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "field_array_length", "field": "tags", "min": 1, "max": 3}
+        ]
+    });
+
+    let output = json!([
+        {"tags": ["a"]},
+        {"tags": []},
+        {"tags": ["a", "b", "c", "d"]},
+        {"tags": "a"},
+        {"id": 5}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    let details: Vec<&str> = verdict
+        .violations
+        .iter()
+        .map(|violation| violation.detail.as_str())
+        .collect();
+    assert_eq!(
+        details,
+        vec![
+            "Row 1 field 'tags' must contain at least 1 items, found 0.",
+            "Row 2 field 'tags' must contain at most 3 items, found 4.",
+            "Row 3 field 'tags' must be an array for field_array_length rule.",
+        ]
+    );
+}
+
+#[test]
+fn star_pointer_tokens_are_wildcards_even_next_to_a_star_key() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "object",
+        "rules": [
+            {"rule": "field_type", "field": "/meta/*", "expected": "number"},
+            {"rule": "field_type", "field": "*", "expected": "string"}
+        ]
+    });
+
+    let output = json!({"*": "top", "meta": {"*": 1, "label": "x"}});
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    let fields: Vec<Option<&str>> = verdict
+        .violations
+        .iter()
+        .map(|violation| violation.field.as_deref())
+        .collect();
+    assert_eq!(fields, vec![Some("/meta/label")]);
+}
+
+#[test]
+fn field_paths_reach_nested_values_and_report_concrete_paths() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "object",
+        "rules": [
+            {"rule": "required_field", "field": "result.items[*].meta.score"},
+            {"rule": "field_type", "field": "/result/items/*/meta/score", "expected": "number"},
+            {"rule": "allowed_values", "field": "result.status", "values": ["ok"]},
+            {"rule": "required_field", "field": "a.b"}
+        ]
+    });
+
+    let output = json!({
+        "a.b": true,
+        "result": {
+            "status": "ok",
+            "items": [
+                {"meta": {"score": 1}},
+                {"meta": {"score": "high"}},
+                {"meta": {}}
+            ]
+        }
+    });
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    let found: Vec<(&str, Option<&str>)> = verdict
+        .violations
+        .iter()
+        .map(|violation| (violation.code, violation.field.as_deref()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("LLMC101", Some("result.items[2].meta.score")),
+            ("LLMC201", Some("/result/items/1/meta/score")),
+            ("LLMC102", Some("/result/items/2/meta/score")),
+        ]
+    );
+    assert_eq!(
+        verdict.violations[0].detail,
+        "Missing required field 'result.items[2].meta.score'."
+    );
+    let position = verdict.violations[1]
+        .position
+        .expect("position of the nested value");
+    assert_eq!(position.line, 13);
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "object",
+        "rules": [{"rule": "required_field", "field": "result.items[x]"}]
+    });
+    write_json(&contract_path, &contract);
+
+    let result = run(&contract_path, &output_path);
+
+    match result {
+        Err(RunError::InvalidContractRule(detail)) => {
+            assert!(detail.contains("'/result.items[x]'"), "{detail}")
+        }
+        other => panic!("expected an invalid contract, got {other:?}"),
+    }
+
+    // A JSON Pointer names keys that do not parse as paths literally.
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "object",
+        "rules": [
+            {"rule": "required_field", "field": "/tags[]"},
+            {"rule": "required_field", "field": "/a~1b"}
+        ]
+    });
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &json!({"tags[]": [], "a/b": 1}));
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+    assert_eq!(verdict.status, VerdictStatus::Pass);
+}
+
+#[test]
+fn key_order_compares_keys_as_written() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "key_order", "keys": ["id", "title", "body"]}
+        ]
+    });
+
+    write_json(&contract_path, &contract);
+    // Written as text: the order of the keys is what is under test.
+    fs::write(
+        &output_path,
+        r#"[
+  {"id": 1, "extra": true, "title": "a", "body": "b"},
+  {"id": 2, "body": "b"},
+  {"title": "a", "id": 3}
+]"#,
+    )
+    .expect("write output");
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    assert_eq!(verdict.violations.len(), 1);
+    let violation = &verdict.violations[0];
+    assert_eq!(violation.code, "LLMC404");
+    assert_eq!(violation.row, Some(2));
+    assert_eq!(violation.expected, Some(json!(["id", "title"])));
+    assert_eq!(violation.actual, Some(json!(["title", "id"])));
+    assert_eq!(
+        violation.detail,
+        "Row 2 has keys out of order: expected 'id', 'title', found 'title', 'id'."
+    );
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "object",
+        "rules": [
+            {"rule": "key_order", "keys": ["id", "title"], "exact": true}
+        ]
+    });
+    write_json(&contract_path, &contract);
+    fs::write(&output_path, r#"{"id": 1, "title": "a", "extra": true}"#).expect("write output");
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    assert_eq!(
+        verdict.violations[0].detail,
+        "Object must have exactly the keys 'id', 'title' in that order, found 'id', 'title', 'extra'."
+    );
+}
+
+#[test]
+fn range_length_uniqueness_and_comparison_rules_check_rows() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "number_range", "field": "score", "min": 0, "max": 1},
+            {"rule": "string_length", "field": "title", "min": 1, "max": 5},
+            {"rule": "unique_values", "field": "id", "normalize": {"case_fold": true}},
+            {"rule": "max_items", "value": 2},
+            {"rule": "field_comparison", "field": "start", "operator": "<=", "other_field": "end"}
+        ]
+    });
+
+    let output = json!([
+        {"id": "a", "score": 0.5, "title": "Short", "start": "2024-01-01", "end": "2024-02-01"},
+        {"id": "A", "score": 1.5, "title": "", "start": "2024-03-01", "end": "2024-02-01"},
+        {"id": "b", "score": "high", "title": "Too long", "start": 1, "end": "2024-02-01"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    let found: Vec<(&str, &str)> = verdict
+        .violations
+        .iter()
+        .map(|violation| (violation.code, violation.detail.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("LLMC310", "Row 1 field 'score' must be at most 1, found 1.5."),
+            ("LLMC202", "Row 2 field 'score' must be a number for number_range rule."),
+            ("LLMC311", "Row 1 field 'title' must be at least 1 characters long, found 0."),
+            ("LLMC311", "Row 2 field 'title' must be at most 5 characters long, found 8."),
+            ("LLMC406", "Row 1 field 'id' value \"A\" duplicates 'id' in row 0."),
+            ("LLMC405", "Top-level array must contain at most 2 items, found 3."),
+            (
+                "LLMC503",
+                "Row 1 field 'start' must be <= field 'end' (\"2024-02-01\")."
+            ),
+            (
+                "LLMC202",
+                "Row 2 field 'start' and field 'end' must both be numbers or both strings for field_comparison rule."
+            ),
+        ]
+    );
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [{"rule": "string_length", "field": "title", "min": 5, "max": 1}]
+    });
+    write_json(&contract_path, &contract);
+
+    let result = run(&contract_path, &output_path);
+
+    assert!(matches!(result, Err(RunError::InvalidContractRule(_))));
+}
+
+#[test]
+fn compiled_contract_verifies_values_from_a_json_string() {
+    let contract = llmc::Contract::from_json_str(
+        r#"{
+            "inputs": ["prompt"],
+            "output_type": "object",
+            "rules": [
+                {"rule": "regex", "field": "sku", "pattern": "^[A-Z]{3}-\\d+$"},
+                {"rule": "any_of", "groups": [[{"rule": "regex", "field": "code", "pattern": "^x"}]]}
+            ]
+        }"#,
+    )
+    .expect("valid contract");
+    let compiled = llmc::CompiledContract::new(contract.clone()).expect("compiles");
+
+    for output in [
+        json!({"sku": "ABC-12", "code": "xy"}),
+        json!({"sku": "abc", "code": "y"}),
+    ] {
+        let expected = llmc::verify_value(&contract, &output).expect("valid contract");
+        let verdict = compiled.verify(&output);
+        assert_eq!(verdict.status, expected.status);
+        assert_eq!(verdict.violations.len(), expected.violations.len());
+    }
+    assert_eq!(
+        compiled.verify(&json!({"sku": "abc", "code": "y"})).status,
+        VerdictStatus::Fail
+    );
+
+    let invalid = llmc::Contract::from_json_str(
+        r#"{"inputs": [], "output_type": "object", "rules": [{"rule": "regex", "field": "a", "pattern": "("}]}"#,
+    );
+    assert!(matches!(invalid, Err(RunError::InvalidContractRegex(_))));
+
+    // Deserializing skips validation, which verifying catches up on.
+    let unvalidated: llmc::Contract = serde_json::from_value(json!({
+        "inputs": [],
+        "output_type": "object",
+        "rules": [{"rule": "regex", "field": "a", "pattern_ref": "no_such_pattern"}]
+    }))
+    .expect("parse contract");
+    assert!(matches!(
+        llmc::verify_value(&unvalidated, &json!({"a": "x"})),
+        Err(RunError::InvalidContractRule(_))
+    ));
+}
+
+#[test]
+fn text_with_json_outputs_have_their_json_extracted() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("transcript.txt");
+
+    let mut contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "object",
+        "rules": [{"rule": "allowed_values", "field": "status", "values": ["open"]}]
+    });
+    write_json(&contract_path, &contract);
+    fs::write(
+        &output_path,
+        "Here is the result:\n\n```json\n{\"status\": \"closed\"}\n```\n\nAnything else?\n",
+    )
+    .expect("write transcript");
+
+    let result = run(&contract_path, &output_path);
+    assert!(matches!(result, Err(RunError::InvalidOutput(_))));
+
+    let options = VerifyOptions {
+        extract: true,
+        ..VerifyOptions::default()
+    };
+    let by_option = verifier::run(&contract_path, &output_path, &options).expect("extracts");
+
+    contract["output_format"] = json!("text_with_json");
+    write_json(&contract_path, &contract);
+    let verdict = run(&contract_path, &output_path).expect("extracts");
+
+    assert_eq!(verdict, by_option);
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    let extraction = verdict.extraction.as_ref().expect("extraction recorded");
+    assert_eq!(extraction.method, ExtractionMethod::FencedBlock);
+    assert_eq!(
+        extraction.note,
+        "JSON extracted from a fenced code block at line 4."
+    );
+    let position = verdict.violations[0].position.expect("position");
+    assert_eq!((position.line, position.column), (4, 12));
+
+    let (value, extraction) =
+        extract_json("Sure! {\"a\": 1} and the full answer: {\"a\": 1, \"b\": [2]} Done.")
+            .expect("embedded json");
+    assert_eq!(value, json!({"a": 1, "b": [2]}));
+    assert_eq!(extraction.method, ExtractionMethod::Embedded);
+    assert_eq!(extraction.position.column, 37);
+    assert!(extract_json("No JSON here.").is_none());
+}
+
+#[test]
+fn embedded_json_checks_type_of_stringified_value() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "embedded_json", "field": "metadata", "expected": "object"}
+        ]
+    });
+
+    let output = json!([
+        {"metadata": "{\"source\": \"crawler\"}"},
+        {"metadata": "[1, 2]"},
+        {"metadata": "{not json"},
+        {"metadata": {"source": "crawler"}}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    assert_eq!(verdict.violations.len(), 3);
+    assert_eq!(
+        verdict.violations[0].detail,
+        "Row 1 field 'metadata' embeds JSON of type 'array', expected 'object'."
+    );
+    assert!(verdict.violations[1]
+        .detail
+        .starts_with("Row 2 field 'metadata' does not contain valid JSON:"));
+    assert_eq!(
+        verdict.violations[2].detail,
+        "Row 3 field 'metadata' must be a string for embedded_json rule."
+    );
+}
+
+#[test]
+fn check_digit_rejects_invalid_codes() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "check_digit", "field": "isbn", "scheme": "isbn13"},
+            {"rule": "check_digit", "field": "isbn10", "scheme": "isbn10"},
+            {"rule": "check_digit", "field": "ean", "scheme": "gtin"},
+            {"rule": "check_digit", "field": "card", "scheme": "luhn"}
+        ]
+    });
+
+    let output = json!([
+        {
+            "isbn": "978-0-306-40615-7",
+            "isbn10": "0-8044-2957-X",
+            "ean": "4006381333931",
+            "card": "4539 1488 0343 6467"
+        },
+        {
+            "isbn": "978-0-306-40615-8",
+            "isbn10": "0-8044-2957-1",
+            "ean": "4006381333932",
+            "card": "4539 1488 0343 6468"
+        }
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    let details: Vec<&str> = verdict
+        .violations
+        .iter()
+        .map(|violation| violation.detail.as_str())
+        .collect();
+    assert_eq!(
+        details,
+        vec![
+            "Row 1 field 'isbn' is not a valid isbn13: check digit mismatch.",
+            "Row 1 field 'isbn10' is not a valid isbn10: check digit mismatch.",
+            "Row 1 field 'ean' is not a valid ean: check digit mismatch.",
+            "Row 1 field 'card' is not a valid luhn: check digit mismatch.",
+        ]
+    );
+}
+
+#[test]
+fn geo_coordinate_checks_ranges_and_bounding_box() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "geo_coordinate", "field": "location"},
+            {
+                "rule": "geo_coordinate",
+                "latitude": "lat",
+                "longitude": "lon",
+                "bbox": {"min_lat": 47.0, "max_lat": 55.0, "min_lon": 5.0, "max_lon": 15.0}
+            }
+        ]
+    });
+
+    let output = json!([
+        {"location": [52.52, 13.405], "lat": 52.52, "lon": 13.405},
+        {"location": [95.0, 13.405], "lat": 48.85, "lon": 2.35},
+        {"location": "52.52,13.405", "lat": "52.52", "lon": 13.405}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    let details: Vec<&str> = verdict
+        .violations
+        .iter()
+        .map(|violation| violation.detail.as_str())
+        .collect();
+    assert_eq!(
+        details,
+        vec![
+            "Row 1 field 'location' has latitude 95 outside [-90, 90].",
+            "Row 2 field 'location' must be a [latitude, longitude] pair.",
+            "Row 1 field 'lat' at (48.85, 2.35) lies outside the bounding box.",
+            "Row 2 field 'lat' must hold numeric coordinates.",
+        ]
+    );
+}
+
+#[test]
+fn covers_values_reports_values_missing_from_all_rows() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {
+                "rule": "covers_values",
+                "field": "method",
+                "values": ["GET", "POST", "PUT", "DELETE"],
+                "normalize": {"case_fold": true}
+            }
+        ]
+    });
+
+    let output = json!([
+        {"method": "get"},
+        {"method": "POST"},
+        {"method": "GET"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    assert_eq!(verdict.violations.len(), 1);
+    assert_eq!(
+        verdict.violations[0].detail,
+        "Field 'method' never takes the value(s) \"PUT\", \"DELETE\" in any row."
+    );
+    assert_eq!(verdict.violations[0].actual, Some(json!(["PUT", "DELETE"])));
+}
+
+#[test]
+fn row_pass_threshold_reports_partial_pass() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "row_pass_threshold": 0.75,
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "field_type", "field": "id", "expected": "number"}
+        ]
+    });
+
+    let output = json!([
+        {"id": 1},
+        {"id": 2},
+        {"id": 3},
+        {"id": "four"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::PartialPass);
+    assert_eq!(verdict.row_pass_rate, Some(0.75));
+    assert_eq!(verdict.rows[3].status, VerdictStatus::Fail);
+    assert_eq!(verdict.rows[3].violations, 1);
+
+    let strict = VerifyOptions {
+        row_pass_threshold: Some(0.9),
+        ..VerifyOptions::default()
+    };
+    let verdict =
+        verifier::run(&contract_path, &output_path, &strict).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    assert_eq!(verdict.row_pass_threshold, Some(0.9));
+
+    let review = VerifyOptions {
+        row_pass_threshold: Some(0.9),
+        row_fail_threshold: Some(0.5),
+        ..VerifyOptions::default()
+    };
+    let verdict =
+        verifier::run(&contract_path, &output_path, &review).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Partial);
+    assert_eq!(verdict.row_fail_threshold, Some(0.5));
+
+    let review = VerifyOptions {
+        row_fail_threshold: Some(0.8),
+        ..review
+    };
+    let verdict =
+        verifier::run(&contract_path, &output_path, &review).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+}
+
+#[test]
+fn returns_invalid_contract_rule_error_for_row_fail_threshold_above_pass_threshold() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "row_pass_threshold": 0.8,
+        "row_fail_threshold": 0.9,
+        "rules": [{"rule": "required_field", "field": "id"}]
+    });
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &json!([{"id": 1}]));
+
+    let result = run(&contract_path, &output_path);
+
+    assert!(matches!(result, Err(RunError::InvalidContractRule(_))));
+}
+
+#[test]
+fn checks_list_every_rule_with_its_outcome() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "regex", "field": "code", "pattern": "^[A-Z]{3}$"},
+            {"rule": "min_items", "value": 1}
+        ]
+    });
+
+    let output = json!([
+        {"id": 1, "code": "ABC"},
+        {"id": 2, "code": "abc"},
+        {"id": 3, "code": "x"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    let checks = serde_json::to_value(&verdict.checks).expect("serialize checks");
+    assert_eq!(
+        checks,
+        json!([
+            {"rule": "required_field", "field": "id", "passed": true, "evaluated": 3, "violations": 0},
+            {"rule": "regex", "field": "code", "passed": false, "evaluated": 3, "violations": 2, "rows": [1, 2]},
+            {"rule": "min_items", "passed": true, "evaluated": 1, "violations": 0}
+        ])
+    );
+}
+
+#[test]
+fn violations_carry_stable_codes() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "field_type", "field": "name", "expected": "string"},
+            {"rule": "regex", "field": "code", "pattern": "^[A-Z]{3}$"}
+        ]
+    });
+
+    let output = json!([
+        {"name": 7, "code": 42},
+        "not an object"
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    let codes: Vec<&str> = verdict
+        .violations
+        .iter()
+        .map(|violation| violation.code)
+        .collect();
+    assert_eq!(
+        codes,
+        vec!["LLMC101", "LLMC002", "LLMC201", "LLMC002", "LLMC202", "LLMC002"]
+    );
+}
+
+#[test]
+fn violations_carry_generated_or_contract_hints() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "regex", "field": "sku", "pattern": "^[A-Z]{3}-\\d{4}$"},
+            {
+                "rule": "allowed_values",
+                "field": "status",
+                "values": ["open", "closed"],
+                "hint": "Use 'open' for active tickets and 'closed' otherwise."
+            }
+        ]
+    });
+
+    let output = json!([
+        {"sku": "abc-1", "status": "pending"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(
+        verdict.violations[0].hint.as_deref(),
+        Some("Field 'sku' must be a string matching ^[A-Z]{3}-\\d{4}$.")
+    );
+    assert_eq!(
+        verdict.violations[1].hint.as_deref(),
+        Some("Use 'open' for active tickets and 'closed' otherwise.")
+    );
+}
+
+#[test]
+fn severity_is_inherited_from_groups_and_rules() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "regex", "field": "code", "pattern": "^[A-Z]{3}$", "severity": "info"}
+        ],
+        "groups": [
+            {
+                "name": "style",
+                "severity": "warning",
+                "rules": [{"rule": "field_type", "field": "name", "expected": "string"}]
+            }
+        ]
+    });
+
+    let output = json!([
+        {"code": "abc", "name": 7}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+    assert_eq!(verdict.violations[0].severity, contract::Severity::Info);
+    assert_eq!(verdict.violations[1].severity, contract::Severity::Warning);
+    assert!(verdict.fails_at(contract::Severity::Info));
+    assert!(verdict.fails_at(contract::Severity::Warning));
+    assert!(!verdict.fails_at(contract::Severity::Error));
+}
+
+#[test]
+fn excerpts_are_redacted_and_truncated() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "min_items", "value": 3},
+            {"rule": "regex", "field": "email", "pattern": "^[a-z]+$"},
+            {"rule": "number_range", "field": "age", "min": 18}
+        ]
+    });
+
+    let output = json!([
+        {"email": "alice@example.com", "note": "first contact", "age": 17}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let options = VerifyOptions {
+        excerpts: Some(ExcerptOptions { max_len: 32 }),
+        redact: vec!["email".to_string(), "age".to_string()],
+        ..VerifyOptions::default()
+    };
+    let verdict =
+        verifier::run(&contract_path, &output_path, &options).expect("verifier should run");
+
+    assert_eq!(
+        verdict.violations[0].excerpt.as_deref(),
+        Some("{\"email\":\"[REDACTED]\",\"note\":\"fi…")
+    );
+    assert_eq!(verdict.violations[1].excerpt, None);
+    let email = &verdict.violations[2];
+    assert_eq!(email.actual, Some(json!("[REDACTED]")));
+    let age = &verdict.violations[3];
+    assert_eq!(age.actual, Some(json!("[REDACTED]")));
+    assert_eq!(age.expected, Some(json!("[REDACTED]")));
+    assert_eq!(
+        age.detail,
+        "Row 0 field 'age' must be at least 18, found [REDACTED]."
+    );
+}
+
+#[test]
+fn redaction_covers_values_of_other_fields_a_rule_reads() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "field_comparison", "field": "paid", "operator": "<=", "other_field": "salary"},
+            {"rule": "approx_equal", "field": "net", "other_field": "salary", "abs_tolerance": 1},
+            {"rule": "any_of", "groups": [
+                [{"rule": "field_comparison", "field": "bonus", "operator": "<", "other_field": "salary"}]
+            ]}
+        ]
+    });
+
+    let output = json!([{"paid": 123456, "net": 5, "bonus": 99999, "salary": 98765}]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let options = VerifyOptions {
+        redact: vec!["salary".to_string()],
+        ..VerifyOptions::default()
+    };
+    let verdict =
+        verifier::run(&contract_path, &output_path, &options).expect("verifier should run");
+
+    assert_eq!(verdict.violations.len(), 3);
+    assert_eq!(
+        verdict.violations[0].detail,
+        "Row 0 field 'paid' must be <= field 'salary' ([REDACTED])."
+    );
+    assert_eq!(verdict.violations[0].actual, Some(json!(123456)));
+    assert_eq!(verdict.violations[1].expected, Some(json!("[REDACTED]")));
+    for violation in &verdict.violations {
+        assert!(!violation.detail.contains("98765"), "{}", violation.detail);
+    }
+}
+
+#[test]
+fn violations_report_positions_in_output_file() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "field_type", "field": "price", "expected": "number"},
+            {"rule": "required_field", "field": "name"}
+        ]
+    });
+
+    write_json(&contract_path, &contract);
+    fs::write(
+        &output_path,
+        "[\n  {\"name\": \"ok\", \"price\": 1},\n  {\"name\": \"bäd\", \"price\": \"2\"},\n  {\"price\": 3}\n]\n",
+    )
+    .expect("write output");
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    let price = verdict.violations[0].position.expect("price position");
+    assert_eq!((price.line, price.column), (3, 28));
+    assert_eq!(price.offset, 60);
+    let price_end = verdict.violations[0].end_position.expect("price end");
+    assert_eq!(
+        (price_end.line, price_end.column, price_end.offset),
+        (3, 31, 63)
+    );
+    let missing = verdict.violations[1].position.expect("row position");
+    assert_eq!((missing.line, missing.column), (4, 3));
+    let missing_end = verdict.violations[1].end_position.expect("row end");
+    assert_eq!((missing_end.line, missing_end.column), (4, 15));
+}
+
+#[test]
+fn positions_of_many_violations_in_a_large_output_are_found_quickly() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [{"rule": "field_type", "field": "price", "expected": "number"}]
+    });
+    write_json(&contract_path, &contract);
+    let rows: Vec<String> = (0..40_000)
+        .map(|idx| format!("  {{\"name\": \"item {idx}\", \"price\": \"{idx}\"}}"))
+        .collect();
+    fs::write(&output_path, format!("[\n{}\n]\n", rows.join(",\n"))).expect("write output");
+
+    let started = std::time::Instant::now();
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    // Rescanning the source for every position took minutes on this input.
+    assert!(started.elapsed() < std::time::Duration::from_secs(30));
+    assert_eq!(verdict.violations.len(), 40_000);
+    let last = verdict.violations[39_999].position.expect("last position");
+    assert_eq!((last.line, last.column), (40_001, 35));
+}
+
+#[test]
+fn violations_include_minimal_passing_examples() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "object",
+        "rules": [
+            {"rule": "regex", "field": "sku", "pattern": "^[A-Z]{3}-\\d{4}(-v\\d+)?$"},
+            {"rule": "allowed_values", "field": "status", "values": ["open", "closed"]},
+            {"rule": "datetime_format", "field": "day", "format": "%d/%m/%Y"},
+            {"rule": "check_digit", "field": "isbn", "scheme": "isbn13"},
+            {"rule": "min_items", "value": 1}
+        ]
+    });
+
+    let output = json!({
+        "sku": "abc",
+        "status": "pending",
+        "day": "2024-01-01",
+        "isbn": "9780306406158"
+    });
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+
+    let examples: Vec<Option<Value>> = verdict
+        .violations
+        .iter()
+        .map(|violation| violation.example.clone())
+        .collect();
+    assert_eq!(
+        examples,
+        vec![
+            Some(json!("AAA-0000")),
+            Some(json!("open")),
+            Some(json!("01/01/2000")),
+            Some(json!("9780000000002")),
+            None
+        ]
+    );
+}
+
+#[test]
+fn messages_are_localized_with_contract_overrides() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "field_type", "field": "price", "expected": "number"}
+        ],
+        "messages": {
+            "de": {"LLMC101": "{row_prefix}Bitte '{field}' ergänzen."}
+        }
+    });
+
+    // Placeholders in values from the output are not expanded.
+    let output = json!([{"price": "2"}, {"id": 2, "price": "{code}"}]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let options = VerifyOptions {
+        locale: Some("de".to_string()),
+        ..VerifyOptions::default()
+    };
+    let verdict =
+        verifier::run(&contract_path, &output_path, &options).expect("verifier should run");
+
+    assert_eq!(
+        verdict.violations[0].detail,
+        "Zeile 0: Bitte 'id' ergänzen."
+    );
+    assert_eq!(
+        verdict.violations[1].detail,
+        "Zeile 0: Feld 'price' muss vom Typ \"number\" sein, ist aber \"2\"."
+    );
+    assert_eq!(
+        verdict.violations[2].detail,
+        "Zeile 1: Feld 'price' muss vom Typ \"number\" sein, ist aber \"{code}\"."
+    );
+    // Hints are not translated.
+    assert_eq!(
+        verdict.violations[1].hint.as_deref(),
+        Some("Field 'price' must be a number.")
+    );
+
+    let options = VerifyOptions {
+        locale: Some("fr".to_string()),
+        ..VerifyOptions::default()
+    };
+    let err = verifier::run(&contract_path, &output_path, &options).expect_err("unknown locale");
+    assert!(matches!(err, RunError::InvalidContractRule(_)));
+}
+
+#[test]
+fn identical_row_violations_are_deduplicated() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "allowed_values", "field": "status", "values": ["open"]}
+        ]
+    });
+
+    let output = json!([
+        {"status": "open"},
+        {"status": "closed"},
+        {"status": "closed"},
+        {"id": 4, "status": "pending"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+    assert_eq!(verdict.violations.len(), 6);
+    let options = VerifyOptions {
+        dedupe: true,
+        ..VerifyOptions::default()
+    };
+    let verdict =
+        verifier::run(&contract_path, &output_path, &options).expect("verifier should run");
+
+    let summary: Vec<(&'static str, Option<usize>, Vec<usize>)> = verdict
+        .violations
+        .iter()
+        .map(|violation| (violation.code, violation.row, violation.rows.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("LLMC101", None, vec![0, 1, 2]),
+            ("LLMC301", None, vec![1, 2]),
+            ("LLMC301", Some(3), vec![]),
+        ]
+    );
+    let messages: Vec<&str> = verdict
+        .violations
+        .iter()
+        .map(|violation| violation.detail.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "3 rows are missing required field 'id'.",
+            "Field 'status' has a disallowed value in 2 rows.",
+            "Row 3 field 'status' has a disallowed value.",
+        ]
+    );
+    assert_eq!(verdict.violations[0].position, None);
+    assert_eq!(verdict.violations[1].end_position, None);
+    assert!(verdict.violations[2].position.is_some());
+    assert_eq!(verdict.status, VerdictStatus::Fail);
+}
+
+#[test]
+fn deduplication_keeps_rules_and_failures_apart_and_localizes_merged_entries() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "any_of", "groups": [
+                [{"rule": "required_field", "field": "email"}],
+                [{"rule": "required_field", "field": "phone"}]
+            ]},
+            {"rule": "any_of", "groups": [
+                [{"rule": "required_field", "field": "street"}],
+                [{"rule": "required_field", "field": "po_box"}]
+            ]}
+        ]
+    });
+
+    let output = json!([{}, {}, {"email": "a@example.com"}]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let options = VerifyOptions {
+        dedupe: true,
+        ..VerifyOptions::default()
+    };
+    let verdict =
+        verifier::run(&contract_path, &output_path, &options).expect("verifier should run");
+    let rows: Vec<(&'static str, Vec<usize>)> = verdict
+        .violations
+        .iter()
+        .map(|violation| (violation.code, violation.rows.clone()))
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            ("LLMC101", vec![0, 1, 2]),
+            ("LLMC701", vec![0, 1]),
+            ("LLMC701", vec![0, 1, 2]),
+        ]
+    );
+    assert_ne!(verdict.violations[1].detail, verdict.violations[2].detail);
+
+    let options = VerifyOptions {
+        dedupe: true,
+        locale: Some("de".to_string()),
+        ..VerifyOptions::default()
+    };
+    let verdict =
+        verifier::run(&contract_path, &output_path, &options).expect("verifier should run");
+    assert_eq!(
+        verdict.violations[0].detail,
+        "In 3 Zeilen: Pflichtfeld 'id' fehlt."
+    );
+}
+
+#[test]
+fn strict_mode_reports_absent_and_unknown_fields() {
+    let dir = tempdir().expect("create temp dir");
+    let contract_path = dir.path().join("contract.json");
+    let output_path = dir.path().join("output.json");
+
+    let contract = json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "allowed_values", "field": "status", "values": ["open"]},
+            {"rule": "regex", "field": "sku", "pattern": "^[A-Z]+$"}
+        ]
+    });
+
+    let output = json!([
+        {"status": "open", "sku": "ABC"},
+        {"sku": "DEF", "note": "extra"}
+    ]);
+
+    write_json(&contract_path, &contract);
+    write_json(&output_path, &output);
+
+    let verdict = run(&contract_path, &output_path).expect("verifier should run");
+    assert_eq!(verdict.status, VerdictStatus::Pass);
+
+    let options = VerifyOptions {
+        strict: true,
+        ..VerifyOptions::default()
+    };
+    let verdict =
+        verifier::run(&contract_path, &output_path, &options).expect("verifier should run");
+    let found: Vec<(&'static str, Option<&str>, Option<usize>)> = verdict
+        .violations
+        .iter()
+        .map(|violation| (violation.code, violation.field.as_deref(), violation.row))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("LLMC103", Some("note"), Some(1)),
+            ("LLMC101", Some("status"), Some(1)),
+        ]
+    );
+
+    let mut duplicated = contract.clone();
+    duplicated["rules"]
+        .as_array_mut()
+        .expect("rules array")
+        .push(json!({"rule": "regex", "field": "sku", "pattern": "^[A-Z]+$"}));
+    write_json(&contract_path, &duplicated);
+    let err = verifier::run(&contract_path, &output_path, &options).expect_err("duplicate rule");
+    assert!(matches!(err, RunError::InvalidContractRule(_)));
+}
+
+#[test]
+fn contract_packs_select_named_contracts_with_shared_definitions() {
+    let dir = tempdir().expect("create temp dir");
+    let pack_path = dir.path().join("pack.json");
+    let output_path = dir.path().join("output.json");
+
+    let pack = json!({
+        "pack": "support",
+        "version": 3,
+        "patterns": {"ticket_id": "^T-[0-9]+$"},
+        "contracts": {
+            "triage": {
+                "inputs": ["ticket"],
+                "output_type": "object",
+                "rules": [{"rule": "regex", "field": "id", "pattern_ref": "ticket_id"}]
+            },
+            "summary": {
+                "contract": "summary",
+                "version": 1,
+                "inputs": ["ticket"],
+                "output_type": "object",
+                "rules": [{"rule": "required_field", "field": "summary"}]
+            }
+        }
+    });
+    write_json(&pack_path, &pack);
+    write_json(&output_path, &json!({"id": "X-1"}));
+
+    let named = |name: &str| VerifyOptions {
+        contract_name: Some(name.to_string()),
+        ..VerifyOptions::default()
+    };
+    let contract = verifier::load_contract(&pack_path, &named("triage")).expect("load triage");
+    assert_eq!(contract.contract.as_deref(), Some("triage"));
+    assert_eq!(contract.version, Some(3));
+    let verdict = verifier::run(&pack_path, &output_path, &named("triage")).expect("run triage");
+    assert_eq!(verdict.violations[0].code, "LLMC303");
+    let verdict = verifier::run(&pack_path, &output_path, &named("summary")).expect("run summary");
+    assert_eq!(verdict.violations[0].code, "LLMC101");
+
+    for options in [named("missing"), VerifyOptions::default()] {
+        let err = verifier::load_contract(&pack_path, &options).expect_err("no such contract");
+        assert!(matches!(err, RunError::ContractNotFound(_)));
+        assert!(err.to_string().contains("'summary', 'triage'"), "{err}");
+    }
+
+    let contract_path = dir.path().join("contract.json");
+    write_json(&contract_path, &pack["contracts"]["summary"]);
+    assert!(verifier::load_contract(&contract_path, &named("summary")).is_ok());
+    let err = verifier::load_contract(&contract_path, &named("triage")).expect_err("wrong name");
+    assert!(matches!(err, RunError::ContractNotFound(_)));
+}
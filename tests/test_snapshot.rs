@@ -0,0 +1,63 @@
+use std::fs;
+
+use serde_json::json;
+use tempfile::tempdir;
+
+use llmc::contract::Contract;
+use llmc::snapshot::{assert_verdict_snapshot, normalize_verdict};
+use llmc::verifier::verify;
+
+fn contract() -> Contract {
+    serde_json::from_value(json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "allowed_values", "field": "status", "values": ["open"]}
+        ]
+    }))
+    .expect("valid contract")
+}
+
+#[test]
+fn normalize_strips_timing_and_sorts_violations() {
+    let document = json!({
+        "status": "fail",
+        "violations": [{"code": "LLMC301", "row": 1}, {"code": "LLMC101", "row": 0}],
+        "meta": {"tool": "llmc", "started_at": "x", "finished_at": "y", "duration_ms": 3},
+        "verdicts": [{"violations": [{"row": 2}, {"row": 1}]}]
+    });
+    assert_eq!(
+        normalize_verdict(&document),
+        json!({
+            "status": "fail",
+            "violations": [{"code": "LLMC101", "row": 0}, {"code": "LLMC301", "row": 1}],
+            "meta": {"tool": "llmc"},
+            "verdicts": [{"violations": [{"row": 1}, {"row": 2}]}]
+        })
+    );
+}
+
+#[test]
+fn snapshot_is_written_then_compared() {
+    let dir = tempdir().expect("create temp dir");
+    let path = dir.path().join("snapshots").join("verdict.json");
+    let verdict = verify(&contract(), &json!([{"status": "closed"}])).expect("valid contract");
+
+    assert_verdict_snapshot(&verdict, &path);
+    assert!(fs::read_to_string(&path)
+        .expect("read snapshot")
+        .contains("LLMC101"));
+    assert_verdict_snapshot(&verdict, &path);
+
+    let changed =
+        verify(&contract(), &json!([{"id": 1, "status": "closed"}])).expect("valid contract");
+    let mismatch = std::panic::catch_unwind(|| assert_verdict_snapshot(&changed, &path))
+        .expect_err("changed verdict must not match");
+    let message = mismatch.downcast_ref::<String>().expect("panic message");
+    assert!(message.contains("LLMC_UPDATE_SNAPSHOTS=1"), "{message}");
+    assert!(
+        message.contains("-      \"code\": \"LLMC101\""),
+        "{message}"
+    );
+}
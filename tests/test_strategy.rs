@@ -0,0 +1,36 @@
+#![cfg(feature = "proptest")]
+
+use proptest::prelude::*;
+use serde_json::json;
+
+use llmc::contract::Contract;
+use llmc::strategy::output_strategy;
+use llmc::verifier::{verify, VerdictStatus};
+
+fn contract() -> Contract {
+    serde_json::from_value(json!({
+        "inputs": ["prompt"],
+        "output_type": "array",
+        "rules": [
+            {"rule": "required_field", "field": "id"},
+            {"rule": "regex", "field": "id", "pattern": "^[A-Z]{3}-[0-9]{4}$"},
+            {"rule": "field_type", "field": "score", "expected": "number"},
+            {"rule": "field_type", "field": "name", "expected": "string"},
+            {"rule": "allowed_values", "field": "status", "values": ["open", "closed"]},
+            {"rule": "const", "field": "kind", "value": "ticket"},
+            {"rule": "field_array_length", "field": "tags", "min": 1, "max": 3},
+            {"rule": "check_digit", "field": "card", "scheme": "luhn"},
+            {"rule": "min_items", "value": 2}
+        ]
+    }))
+    .expect("valid contract")
+}
+
+proptest! {
+    #[test]
+    fn generated_outputs_pass_the_contract(output in output_strategy(&contract())) {
+        let verdict = verify(&contract(), &output).expect("valid contract");
+        prop_assert_eq!(verdict.status, VerdictStatus::Pass, "{:?}", verdict.violations);
+        prop_assert!(output.as_array().expect("array output").len() >= 2);
+    }
+}